@@ -1,9 +1,87 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, Clone)]
 pub struct Settings {
     pub application_port: u16,
     pub workspaces_path: String,
+    #[serde(default)]
+    pub import_allowed_paths: Vec<String>,
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u64,
+    #[serde(default = "default_trash_purge_interval_seconds")]
+    pub trash_purge_interval_seconds: u64,
+    #[serde(default)]
+    pub templates: HashMap<String, TemplateSettings>,
+    pub default_template: Option<String>,
+    #[serde(default)]
+    pub quotas: QuotaSettings,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct TemplateSettings {
+    pub path: String,
+    #[serde(default = "default_template_commit_message")]
+    pub commit_message: String,
+}
+
+#[derive(serde::Deserialize, Clone, Default)]
+pub struct QuotaSettings {
+    pub max_repository_size: Option<u64>,
+    pub max_file_size: Option<u64>,
+    pub max_files: Option<u64>,
+    #[serde(default)]
+    pub workspace_overrides: Vec<WorkspaceQuotaSettings>,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct WorkspaceQuotaSettings {
+    pub workspace: String,
+    pub max_repository_size: Option<u64>,
+    pub max_file_size: Option<u64>,
+    pub max_files: Option<u64>,
+}
+
+#[derive(serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuotaLimits {
+    pub max_repository_size: Option<u64>,
+    pub max_file_size: Option<u64>,
+    pub max_files: Option<u64>,
+}
+
+impl QuotaSettings {
+    // The limits of a workspace override fall back to the global limits when they are not set.
+    pub fn get_limits(&self, workspace_name: &str) -> QuotaLimits {
+        let limits = QuotaLimits {
+            max_repository_size: self.max_repository_size,
+            max_file_size: self.max_file_size,
+            max_files: self.max_files,
+        };
+        match self
+            .workspace_overrides
+            .iter()
+            .find(|o| o.workspace == workspace_name)
+        {
+            Some(o) => QuotaLimits {
+                max_repository_size: o.max_repository_size.or(limits.max_repository_size),
+                max_file_size: o.max_file_size.or(limits.max_file_size),
+                max_files: o.max_files.or(limits.max_files),
+            },
+            None => limits,
+        }
+    }
+}
+
+fn default_template_commit_message() -> String {
+    "Initial commit".to_string()
+}
+
+fn default_trash_retention_days() -> u64 {
+    30
+}
+
+fn default_trash_purge_interval_seconds() -> u64 {
+    60 * 60
 }
 
 pub fn get_configuration(configuration_file: &PathBuf) -> Result<Settings, config::ConfigError> {
@@ -0,0 +1,319 @@
+use actix_web::dev::Payload;
+use actix_web::{error, web, FromRequest, HttpRequest};
+use git2::Reference;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::{ready, Future, Ready};
+use std::ops::Deref;
+use std::pin::Pin;
+
+const MAX_NAME_LENGTH: usize = 255;
+const MAX_PATH_LENGTH: usize = 4096;
+
+// Workspace names become directory names inside the workspaces path: only letters, digits,
+// '.', '_' and '-' are accepted, and a leading '.' is refused so that ".", ".." and the trash
+// directory cannot be addressed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceName(String);
+
+// Branch names must be valid reference names under "refs/heads/", which rules out revspec
+// syntax such as "HEAD~1", "master@{1}" or "a..b". Names ending in "HEAD" are refused as well,
+// so that "HEAD", "ORIG_HEAD" or "MERGE_HEAD" are never mistaken for a branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchName(String);
+
+// Tag names follow the same rules as branch names, under "refs/tags/".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagName(String);
+
+// File names address a single file at the root of the workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileName(String);
+
+// File paths address a file also inside directories, and every component follows the rules of
+// the file names. They come from the "file_name" segment, or from the "path" query parameter on
+// the routes without that segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilePath(String);
+
+// Optional JSON bodies: a missing body gives the default value, while a body that does not
+// parse is refused instead of being silently replaced by the defaults.
+#[derive(Debug, Clone, Default)]
+pub struct OptionalJson<T>(T);
+
+impl WorkspaceName {
+    pub fn parse(name: &str) -> Result<WorkspaceName, String> {
+        if name.is_empty() || name.len() > MAX_NAME_LENGTH {
+            return Err(format!(
+                "The workspace name must be between 1 and {} characters long.",
+                MAX_NAME_LENGTH
+            ));
+        }
+        if name.starts_with('.') {
+            return Err("The workspace name cannot start with '.'.".to_string());
+        }
+        if !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-')
+        {
+            return Err(
+                "The workspace name can only contain letters, digits, '.', '_' and '-'."
+                    .to_string(),
+            );
+        }
+        Ok(WorkspaceName(name.to_string()))
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl BranchName {
+    pub fn parse(name: &str) -> Result<BranchName, String> {
+        if name.is_empty() || name.len() > MAX_NAME_LENGTH {
+            return Err(format!(
+                "The branch name must be between 1 and {} characters long.",
+                MAX_NAME_LENGTH
+            ));
+        }
+        if name.starts_with('-')
+            || name.ends_with("HEAD")
+            || !Reference::is_valid_name(&format!("refs/heads/{}", name))
+        {
+            return Err(format!("The branch name {} is not valid.", name));
+        }
+        Ok(BranchName(name.to_string()))
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TagName {
+    pub fn parse(name: &str) -> Result<TagName, String> {
+        if name.is_empty() || name.len() > MAX_NAME_LENGTH {
+            return Err(format!(
+                "The tag name must be between 1 and {} characters long.",
+                MAX_NAME_LENGTH
+            ));
+        }
+        if name.starts_with('-') || !Reference::is_valid_name(&format!("refs/tags/{}", name)) {
+            return Err(format!("The tag name {} is not valid.", name));
+        }
+        Ok(TagName(name.to_string()))
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl FileName {
+    pub fn parse(name: &str) -> Result<FileName, String> {
+        if name.is_empty() || name.len() > MAX_NAME_LENGTH {
+            return Err(format!(
+                "The file name must be between 1 and {} characters long.",
+                MAX_NAME_LENGTH
+            ));
+        }
+        if name == "." || name == ".." || name == ".git" {
+            return Err(format!("The file name {} is reserved.", name));
+        }
+        if name
+            .chars()
+            .any(|c| c == '/' || c == '\\' || c.is_control())
+        {
+            return Err(
+                "The file name cannot contain path separators or control characters.".to_string(),
+            );
+        }
+        Ok(FileName(name.to_string()))
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl FilePath {
+    pub fn parse(path: &str) -> Result<FilePath, String> {
+        if path.is_empty() || path.len() > MAX_PATH_LENGTH {
+            return Err(format!(
+                "The file path must be between 1 and {} characters long.",
+                MAX_PATH_LENGTH
+            ));
+        }
+        for component in path.split('/') {
+            FileName::parse(component)?;
+        }
+        Ok(FilePath(path.to_string()))
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl Deref for WorkspaceName {
+    type Target = String;
+
+    fn deref(&self) -> &String {
+        &self.0
+    }
+}
+
+impl Deref for BranchName {
+    type Target = String;
+
+    fn deref(&self) -> &String {
+        &self.0
+    }
+}
+
+impl Deref for TagName {
+    type Target = String;
+
+    fn deref(&self) -> &String {
+        &self.0
+    }
+}
+
+impl Deref for FileName {
+    type Target = String;
+
+    fn deref(&self) -> &String {
+        &self.0
+    }
+}
+
+impl Deref for FilePath {
+    type Target = String;
+
+    fn deref(&self) -> &String {
+        &self.0
+    }
+}
+
+impl fmt::Display for WorkspaceName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl fmt::Display for BranchName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl fmt::Display for TagName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl fmt::Display for FileName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromRequest for WorkspaceName {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(extract(req, "workspace_name", WorkspaceName::parse))
+    }
+}
+
+impl FromRequest for BranchName {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(extract(req, "branch_name", BranchName::parse))
+    }
+}
+
+impl FromRequest for TagName {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(extract(req, "tag_name", TagName::parse))
+    }
+}
+
+impl FromRequest for FileName {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(extract(req, "file_name", FileName::parse))
+    }
+}
+
+impl FromRequest for FilePath {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        if req.match_info().get("file_name").is_some() {
+            return ready(extract(req, "file_name", FilePath::parse));
+        }
+        let path = web::Query::<HashMap<String, String>>::from_query(req.query_string())
+            .ok()
+            .and_then(|q| q.get("path").cloned());
+        ready(match path {
+            Some(path) => FilePath::parse(&path).map_err(error::ErrorBadRequest),
+            None => Err(error::ErrorBadRequest(
+                "The query parameter path is missing.",
+            )),
+        })
+    }
+}
+
+impl<T> OptionalJson<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: DeserializeOwned + Default + 'static> FromRequest for OptionalJson<T> {
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let body = web::Bytes::from_request(req, payload);
+        Box::pin(async move {
+            let body = body.await?;
+            if body.iter().all(u8::is_ascii_whitespace) {
+                return Ok(OptionalJson(T::default()));
+            }
+            serde_json::from_slice(&body)
+                .map(OptionalJson)
+                .map_err(|e| error::ErrorBadRequest(format!("The body is not valid: {}.", e)))
+        })
+    }
+}
+
+fn extract<T>(
+    req: &HttpRequest,
+    segment: &str,
+    parse: fn(&str) -> Result<T, String>,
+) -> Result<T, actix_web::Error> {
+    let value = match req.match_info().get(segment) {
+        Some(v) => v,
+        None => {
+            return Err(error::ErrorInternalServerError(format!(
+                "The route does not define the segment {}.",
+                segment
+            )))
+        }
+    };
+    parse(value).map_err(error::ErrorBadRequest)
+}
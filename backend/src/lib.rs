@@ -1,3 +1,4 @@
 pub mod configuration;
+pub mod extractors;
 pub mod routes;
 pub mod startup;
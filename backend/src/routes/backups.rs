@@ -0,0 +1,298 @@
+use crate::extractors::{BranchName, WorkspaceName};
+use crate::routes::{check_delete_or_reset, check_rebase_in_progress, update_branch};
+use actix_web::{web, HttpResponse};
+use git2::{BranchType, Oid, Repository};
+use std::cmp::Reverse;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ResetBranch {
+    pub commit_uuid: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct BackupPath {
+    pub backup_id: String,
+}
+
+// A backup keeps a previous tip of a branch under "refs/backups/{branch_name}/{timestamp}", so
+// the commits stay reachable after the branch has been moved.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct BranchBackup {
+    pub id: String,
+    pub branch: String,
+    pub commit_uuid: String,
+    pub created_at: u64,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct ResetResult {
+    branch: String,
+    commit_uuid: String,
+    backup: Option<BranchBackup>,
+}
+
+// curl -X POST -H 'Content-Type: application/json' -d '{"commit_uuid":"commit_uuid"}' -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}/reset
+pub async fn reset_branch(
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
+    reset: web::Json<ResetBranch>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let workspace = PathBuf::from(workspace_path.as_str()).join(workspace_name.as_str());
+    let branch_name = branch_name.into_inner();
+    let commit_id = match Oid::from_str(&reset.commit_uuid) {
+        Ok(oid) => oid,
+        Err(_) => {
+            return HttpResponse::BadRequest().body(format!(
+                "The commit id {} is not valid.",
+                &reset.commit_uuid
+            ))
+        }
+    };
+    let repository = match Repository::open(&workspace) {
+        Ok(r) => r,
+        Err(e) => {
+            println!("Error while retrieving the repository: {:#?}", e);
+            return HttpResponse::NotFound().finish();
+        }
+    };
+    if let Some(response) = check_rebase_in_progress(&repository) {
+        return response;
+    }
+    let tip = match repository
+        .find_branch(&branch_name, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+    {
+        Ok(c) => c.id(),
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    if let Some(response) = check_delete_or_reset(&repository, &branch_name) {
+        return response;
+    }
+    if repository.find_commit(commit_id).is_err() {
+        return HttpResponse::NotFound().finish();
+    }
+    if tip == commit_id {
+        return HttpResponse::Ok().json(ResetResult {
+            branch: branch_name,
+            commit_uuid: tip.to_string(),
+            backup: None,
+        });
+    }
+    if !repository
+        .graph_descendant_of(tip, commit_id)
+        .unwrap_or(false)
+    {
+        return HttpResponse::BadRequest().body(format!(
+            "The commit {} is not part of the history of the branch {}.",
+            commit_id, &branch_name
+        ));
+    }
+    let backup = match create_backup(&repository, &branch_name, tip) {
+        Ok(b) => b,
+        Err(e) => {
+            eprintln!(
+                "Error while creating the backup of the branch {}: {:#?}",
+                &branch_name, e
+            );
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    match update_branch(&repository, &branch_name, commit_id, "reset") {
+        Ok(()) => HttpResponse::Ok().json(ResetResult {
+            branch: branch_name,
+            commit_uuid: commit_id.to_string(),
+            backup: Some(backup),
+        }),
+        Err(e) => {
+            eprintln!(
+                "Error while resetting the branch {}: {:#?}",
+                &branch_name, e
+            );
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+// curl -X GET -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}/backups
+pub async fn retrieve_branch_backups(
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let workspace = PathBuf::from(workspace_path.as_str()).join(workspace_name.as_str());
+    let repository = match Repository::open(&workspace) {
+        Ok(r) => r,
+        Err(e) => {
+            println!("Error while retrieving the repository: {:#?}", e);
+            return HttpResponse::NotFound().finish();
+        }
+    };
+    match get_backups(&repository, &branch_name) {
+        Ok(backups) => HttpResponse::Ok().json(backups),
+        Err(e) => {
+            eprintln!(
+                "Error while retrieving the backups of the branch {}: {:#?}",
+                branch_name.as_str(),
+                e
+            );
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+// curl -X POST -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}/backups/{backup_id}/restore
+pub async fn restore_branch_backup(
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
+    backup_path: web::Path<BackupPath>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let workspace = PathBuf::from(workspace_path.as_str()).join(workspace_name.as_str());
+    let branch_name = branch_name.into_inner();
+    let repository = match Repository::open(&workspace) {
+        Ok(r) => r,
+        Err(e) => {
+            println!("Error while retrieving the repository: {:#?}", e);
+            return HttpResponse::NotFound().finish();
+        }
+    };
+    if let Some(response) = check_rebase_in_progress(&repository) {
+        return response;
+    }
+    let backup = match find_backup(&repository, &branch_name, &backup_path.backup_id) {
+        Some(b) => b,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    if let Some(response) = check_delete_or_reset(&repository, &branch_name) {
+        return response;
+    }
+    let commit_id = match Oid::from_str(&backup.commit_uuid) {
+        Ok(oid) => oid,
+        Err(e) => {
+            eprintln!("Error while reading the backup {}: {:#?}", &backup.id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    // The current tip is kept as a new backup, so that a restore can be undone as well. A
+    // deleted branch is created again from the backup.
+    let current_tip = repository
+        .find_branch(&branch_name, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .map(|c| c.id())
+        .ok();
+    let new_backup = match current_tip {
+        Some(tip) if tip != commit_id => match create_backup(&repository, &branch_name, tip) {
+            Ok(b) => Some(b),
+            Err(e) => {
+                eprintln!(
+                    "Error while creating the backup of the branch {}: {:#?}",
+                    &branch_name, e
+                );
+                return HttpResponse::InternalServerError().finish();
+            }
+        },
+        _ => None,
+    };
+    let restore = update_branch(&repository, &branch_name, commit_id, "restore backup")
+        .and_then(|_| {
+            repository.find_reference(&get_backup_reference_name(&branch_name, &backup.id))
+        })
+        .and_then(|mut r| r.delete());
+    match restore {
+        Ok(()) => HttpResponse::Ok().json(ResetResult {
+            branch: branch_name,
+            commit_uuid: commit_id.to_string(),
+            backup: new_backup,
+        }),
+        Err(e) => {
+            eprintln!(
+                "Error while restoring the backup {} of the branch {}: {:#?}",
+                &backup.id, &branch_name, e
+            );
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+pub fn create_backup(
+    repository: &Repository,
+    branch_name: &str,
+    oid: Oid,
+) -> Result<BranchBackup, git2::Error> {
+    let mut created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    while repository
+        .find_reference(&get_backup_reference_name(
+            branch_name,
+            &created_at.to_string(),
+        ))
+        .is_ok()
+    {
+        created_at += 1;
+    }
+    let id = created_at.to_string();
+    repository.reference(
+        &get_backup_reference_name(branch_name, &id),
+        oid,
+        false,
+        &format!("backup of {}", branch_name),
+    )?;
+    Ok(BranchBackup {
+        id,
+        branch: branch_name.to_string(),
+        commit_uuid: oid.to_string(),
+        created_at,
+    })
+}
+
+fn get_backup_reference_name(branch_name: &str, backup_id: &str) -> String {
+    format!("refs/backups/{}/{}", branch_name, backup_id)
+}
+
+// The newest backups come first.
+fn get_backups(
+    repository: &Repository,
+    branch_name: &str,
+) -> Result<Vec<BranchBackup>, git2::Error> {
+    let prefix = format!("refs/backups/{}/", branch_name);
+    let mut backups = Vec::new();
+    for reference in repository.references_glob(&format!("{}*", prefix))? {
+        let reference = reference?;
+        let id = match reference.name().and_then(|n| n.strip_prefix(&prefix)) {
+            Some(id) => id.to_string(),
+            None => continue,
+        };
+        // Backups of "feature/x" also match the prefix of the branch "feature".
+        let created_at = match id.parse::<u64>() {
+            Ok(t) => t,
+            Err(_) => continue,
+        };
+        backups.push(BranchBackup {
+            id,
+            branch: branch_name.to_string(),
+            commit_uuid: reference.peel_to_commit()?.id().to_string(),
+            created_at,
+        });
+    }
+    backups.sort_by_key(|b| Reverse(b.created_at));
+    Ok(backups)
+}
+
+fn find_backup(
+    repository: &Repository,
+    branch_name: &str,
+    backup_id: &str,
+) -> Option<BranchBackup> {
+    if backup_id.is_empty() || !backup_id.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    get_backups(repository, branch_name)
+        .ok()?
+        .into_iter()
+        .find(|b| b.id == backup_id)
+}
@@ -1,10 +1,72 @@
+use crate::extractors::{BranchName, OptionalJson, WorkspaceName};
+use crate::routes::{check_delete_or_reset, check_rebase_in_progress, find_branch_or_tag_commit};
 use actix_web::{web, HttpResponse};
-use git2::{Branch, BranchType, Commit, ObjectType, Repository};
+use git2::{Branch, BranchType, Commit, Delta, Oid, Patch, Repository};
 use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
 
+// The metadata is stored in the repository config under "branch.{branch_name}" when the branch
+// is created, so it is missing for the branches created outside of the application.
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 struct GitBranch {
     name: String,
+    commit_uuid: Option<String>,
+    commit_time: Option<i64>,
+    description: Option<String>,
+    creator: Option<String>,
+    base: Option<String>,
+    created_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct CompareQuery {
+    pub base: String,
+    pub head: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct BranchComparison {
+    base: String,
+    head: String,
+    merge_base: Option<String>,
+    ahead_by: usize,
+    behind_by: usize,
+    ahead_commits: Vec<GitLog>,
+    behind_commits: Vec<GitLog>,
+    files: Vec<ChangedFile>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct ChangedFile {
+    name: String,
+    status: String,
+    additions: usize,
+    deletions: usize,
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct NewBranch {
+    pub start_point: Option<String>,
+    pub description: Option<String>,
+    pub creator: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct RenameBranch {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct DeleteBranchQuery {
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct DeletedBranch {
+    name: String,
+    commit_uuid: String,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
@@ -15,10 +77,10 @@ struct GitLog {
 
 // curl -X GET -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches
 pub async fn retrieve_branches(
-    workspace_name_param: web::Path<String>,
+    workspace_name: WorkspaceName,
     workspace_path: web::Data<String>,
 ) -> HttpResponse {
-    let workspace_name = workspace_name_param.into_inner();
+    let workspace_name = workspace_name.into_inner();
     let workspace_directory = PathBuf::from(&workspace_path.as_str());
     let workspace_path = get_workspace_path(workspace_directory, &workspace_name);
     let repository = match get_repository(&workspace_path) {
@@ -32,7 +94,7 @@ pub async fn retrieve_branches(
     match repository.branches(Some(BranchType::Local)) {
         Ok(branches) => {
             for b in branches {
-                match get_branch_name(b) {
+                match get_branch_name(&repository, b) {
                     Ok(branch_name) => vec.push(branch_name),
                     Err(e) => {
                         println!("{}", &e.message());
@@ -49,15 +111,18 @@ pub async fn retrieve_branches(
     HttpResponse::Ok().json(vec)
 }
 
-// curl -X POST -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}
+// curl -X POST -H 'Content-Type: application/json' -d '{"start_point":"master","description":"branch description","creator":"Marco Cella"}' -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}
 pub async fn create_branches(
-    path_param: web::Path<(String, String)>,
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
+    new_branch: OptionalJson<NewBranch>,
     workspace_path: web::Data<String>,
 ) -> HttpResponse {
-    let workspace_name = &path_param.0;
+    let workspace_name = &workspace_name.into_inner();
     let workspace_directory = PathBuf::from(&workspace_path.as_str());
     let workspace_path = get_workspace_path(workspace_directory, workspace_name);
-    let branch_name = &path_param.1;
+    let branch_name = &branch_name.into_inner();
+    let new_branch = new_branch.into_inner();
     let repository = match get_repository(&workspace_path) {
         Some(r) => r,
         None => {
@@ -65,22 +130,63 @@ pub async fn create_branches(
             return HttpResponse::InternalServerError().finish();
         }
     };
-    if let Err(e) = create_branch(repository, &branch_name) {
+    if let Some(response) = check_rebase_in_progress(&repository) {
+        return response;
+    }
+    if repository
+        .find_branch(branch_name, BranchType::Local)
+        .is_ok()
+    {
+        return HttpResponse::Conflict()
+            .body(format!("The branch {} already exists.", branch_name));
+    }
+    let base = new_branch
+        .start_point
+        .clone()
+        .unwrap_or_else(|| get_default_branch(&repository));
+    let start_commit = match &new_branch.start_point {
+        Some(start_point) => match find_start_point(&repository, start_point) {
+            Some(c) => c,
+            None => {
+                return HttpResponse::NotFound()
+                    .body(format!("The start point {} does not exist.", start_point))
+            }
+        },
+        None => {
+            let default_branch = get_default_branch(&repository);
+            match find_start_point(&repository, &default_branch) {
+                Some(c) => c,
+                None => {
+                    eprintln!("The default branch {} does not exist.", &default_branch);
+                    return HttpResponse::InternalServerError().finish();
+                }
+            }
+        }
+    };
+    if let Err(e) = create_branch(&repository, branch_name, &start_commit) {
         eprintln!("Error while creating the branch {}: {:#?}", &branch_name, e);
         return HttpResponse::InternalServerError().finish();
     }
+    if let Err(e) = set_branch_metadata(&repository, branch_name, &new_branch, &base) {
+        eprintln!(
+            "Error while storing the metadata of the branch {}: {:#?}",
+            &branch_name, e
+        );
+        return HttpResponse::InternalServerError().finish();
+    }
     HttpResponse::Ok().finish()
 }
 
 // curl -X PUT -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}
 pub async fn set_current_branch(
-    path_param: web::Path<(String, String)>,
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
     workspace_path: web::Data<String>,
 ) -> HttpResponse {
-    let workspace_name = &path_param.0;
+    let workspace_name = &workspace_name.into_inner();
     let workspace_directory = PathBuf::from(&workspace_path.as_str());
     let workspace_path = get_workspace_path(workspace_directory, workspace_name);
-    let branch_name = &path_param.1;
+    let branch_name = &branch_name.into_inner();
     let repository = match get_repository(&workspace_path) {
         Some(r) => r,
         None => {
@@ -88,6 +194,9 @@ pub async fn set_current_branch(
             return HttpResponse::InternalServerError().finish();
         }
     };
+    if let Some(response) = check_rebase_in_progress(&repository) {
+        return response;
+    }
     if let Err(e) = set_branch(&repository, branch_name) {
         eprintln!(
             "Error while setting the current the branch {}: {:#?}",
@@ -98,15 +207,17 @@ pub async fn set_current_branch(
     HttpResponse::Ok().finish()
 }
 
-// curl -X GET -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}/logs
-pub async fn get_branch_logs(
-    path_param: web::Path<(String, String)>,
+// curl -X DELETE -v "http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}?force=true"
+pub async fn delete_branch(
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
+    query: web::Query<DeleteBranchQuery>,
     workspace_path: web::Data<String>,
 ) -> HttpResponse {
-    let workspace_name = &path_param.0;
+    let workspace_name = &workspace_name.into_inner();
     let workspace_directory = PathBuf::from(&workspace_path.as_str());
     let workspace_path = get_workspace_path(workspace_directory, workspace_name);
-    let branch_name = &path_param.1;
+    let branch_name = &branch_name.into_inner();
     let repository = match get_repository(&workspace_path) {
         Some(r) => r,
         None => {
@@ -114,14 +225,155 @@ pub async fn get_branch_logs(
             return HttpResponse::InternalServerError().finish();
         }
     };
-    if let Err(e) = set_branch(&repository, branch_name) {
-        eprintln!(
-            "Error while setting the current the branch {}: {:#?}",
-            branch_name, e
-        );
+    if let Some(response) = check_rebase_in_progress(&repository) {
+        return response;
+    }
+    let mut branch = match repository.find_branch(branch_name, BranchType::Local) {
+        Ok(b) => b,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    if let Some(response) = check_delete_or_reset(&repository, branch_name) {
+        return response;
+    }
+    if branch.is_head() {
+        return HttpResponse::Conflict().body(format!(
+            "The branch {} is the current branch and cannot be deleted.",
+            branch_name
+        ));
+    }
+    let default_branch = get_default_branch(&repository);
+    if branch_name == &default_branch {
+        return HttpResponse::Conflict().body(format!(
+            "The branch {} is the default branch and cannot be deleted.",
+            branch_name
+        ));
+    }
+    let tip = match branch.get().peel_to_commit() {
+        Ok(c) => c.id(),
+        Err(e) => {
+            eprintln!(
+                "Error while retrieving the branch {}: {:#?}",
+                branch_name, e
+            );
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    if !query.force {
+        match is_merged(&repository, &default_branch, tip) {
+            Ok(true) => {}
+            Ok(false) => {
+                return HttpResponse::Conflict().body(format!(
+                    "The branch {} is not merged. Set force=true to delete it.",
+                    branch_name
+                ))
+            }
+            Err(e) => {
+                eprintln!("Error while checking the branch {}: {:#?}", branch_name, e);
+                return HttpResponse::InternalServerError().finish();
+            }
+        }
+    }
+    if let Err(e) = branch.delete() {
+        eprintln!("Error while deleting the branch {}: {:#?}", branch_name, e);
         return HttpResponse::InternalServerError().finish();
     }
-    match get_logs(&repository) {
+    HttpResponse::Ok().json(DeletedBranch {
+        name: branch_name.clone(),
+        commit_uuid: tip.to_string(),
+    })
+}
+
+// curl -X POST -H 'Content-Type: application/json' -d '{"name":"new_branch_name"}' -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}/rename
+pub async fn rename_branch(
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
+    rename: web::Json<RenameBranch>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let workspace_name = &workspace_name.into_inner();
+    let workspace_directory = PathBuf::from(&workspace_path.as_str());
+    let workspace_path = get_workspace_path(workspace_directory, workspace_name);
+    let branch_name = &branch_name.into_inner();
+    let new_branch_name = match BranchName::parse(&rename.name) {
+        Ok(b) => b.into_inner(),
+        Err(reason) => return HttpResponse::BadRequest().body(reason),
+    };
+    let repository = match get_repository(&workspace_path) {
+        Some(r) => r,
+        None => {
+            println!("Error while retrieving the repository");
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    if let Some(response) = check_rebase_in_progress(&repository) {
+        return response;
+    }
+    let mut branch = match repository.find_branch(branch_name, BranchType::Local) {
+        Ok(b) => b,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    if let Some(response) = check_delete_or_reset(&repository, branch_name) {
+        return response;
+    }
+    if repository
+        .find_branch(&new_branch_name, BranchType::Local)
+        .is_ok()
+    {
+        return HttpResponse::Conflict()
+            .body(format!("The branch {} already exists.", new_branch_name));
+    }
+    // libgit2 moves HEAD together with the branch when the renamed branch is checked out.
+    let renamed = match branch.rename(&new_branch_name, false) {
+        Ok(b) => b,
+        Err(e) => {
+            eprintln!("Error while renaming the branch {}: {:#?}", branch_name, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    if &get_default_branch(&repository) == branch_name {
+        if let Err(e) = repository
+            .config()
+            .and_then(|mut c| c.set_str("docversions.defaultbranch", &new_branch_name))
+        {
+            eprintln!("Error while updating the default branch: {:#?}", e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+    // libgit2 moves the "branch.{branch_name}" config section, and so the metadata, as well.
+    match get_branch_name(&repository, Ok((renamed, BranchType::Local))) {
+        Ok(b) => HttpResponse::Ok().json(b),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+// curl -X GET -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name|tag_name}/logs
+pub async fn get_branch_logs(
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let workspace_name = &workspace_name.into_inner();
+    let workspace_directory = PathBuf::from(&workspace_path.as_str());
+    let workspace_path = get_workspace_path(workspace_directory, workspace_name);
+    let branch_name = &branch_name.into_inner();
+    let repository = match get_repository(&workspace_path) {
+        Some(r) => r,
+        None => {
+            println!("Error while retrieving the repository");
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    let last_commit = match find_branch_or_tag_commit(&repository, branch_name) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!(
+                "Error while retrieving the last commit of the branch {}: {:#?}",
+                branch_name, e
+            );
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    match get_logs(&repository, &last_commit) {
         Ok(logs) => HttpResponse::Ok().json(logs),
         Err(e) => {
             eprintln!(
@@ -133,6 +385,63 @@ pub async fn get_branch_logs(
     }
 }
 
+// curl -X GET -v "http://127.0.0.1:8000/workspaces/{workspace_name}/compare?base=master&head={branch_name}"
+pub async fn compare_branches(
+    workspace_name: WorkspaceName,
+    query: web::Query<CompareQuery>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let workspace_name = &workspace_name.into_inner();
+    let workspace_directory = PathBuf::from(&workspace_path.as_str());
+    let workspace_path = get_workspace_path(workspace_directory, workspace_name);
+    let (base, head) = match (
+        BranchName::parse(&query.base),
+        BranchName::parse(&query.head),
+    ) {
+        (Ok(base), Ok(head)) => (base.into_inner(), head.into_inner()),
+        (Err(reason), _) | (_, Err(reason)) => return HttpResponse::BadRequest().body(reason),
+    };
+    let repository = match get_repository(&workspace_path) {
+        Some(r) => r,
+        None => {
+            println!("Error while retrieving the repository");
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    let base_commit = match repository
+        .find_branch(&base, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+    {
+        Ok(c) => c,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    let head_commit = match repository
+        .find_branch(&head, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+    {
+        Ok(c) => c,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    match compare_commits(&repository, &base_commit, &head_commit) {
+        Ok((merge_base, ahead_commits, behind_commits, files)) => {
+            HttpResponse::Ok().json(BranchComparison {
+                base,
+                head,
+                merge_base: merge_base.map(|oid| oid.to_string()),
+                ahead_by: ahead_commits.len(),
+                behind_by: behind_commits.len(),
+                ahead_commits,
+                behind_commits,
+                files,
+            })
+        }
+        Err(e) => {
+            eprintln!("Error while comparing {} and {}: {:#?}", &base, &head, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
 fn get_workspace_path(workspace_directory: PathBuf, workspace_name: &String) -> String {
     let mut workspace = workspace_directory.clone();
     workspace.push(workspace_name);
@@ -150,12 +459,26 @@ fn get_repository(workspace_path: &str) -> Option<Repository> {
     Some(repository)
 }
 
-fn get_branch_name(b: Result<(Branch, BranchType), git2::Error>) -> Result<GitBranch, git2::Error> {
+fn get_branch_name(
+    repository: &Repository,
+    b: Result<(Branch, BranchType), git2::Error>,
+) -> Result<GitBranch, git2::Error> {
     match b {
         Ok((branch, _)) => match branch.name() {
             Ok(name) => {
+                let name = String::from(name.unwrap());
+                let tip = branch.get().peel_to_commit().ok();
+                let config = repository.config()?;
+                let get_metadata =
+                    |key: &str| config.get_string(&format!("branch.{}.{}", name, key)).ok();
                 let branch = GitBranch {
-                    name: String::from(name.unwrap()),
+                    commit_uuid: tip.as_ref().map(|c| c.id().to_string()),
+                    commit_time: tip.as_ref().map(|c| c.time().seconds()),
+                    description: get_metadata("description"),
+                    creator: get_metadata("creator"),
+                    base: get_metadata("base"),
+                    created_at: get_metadata("created").and_then(|t| t.parse().ok()),
+                    name,
                 };
                 return Ok(branch);
             }
@@ -171,29 +494,79 @@ fn get_branch_name(b: Result<(Branch, BranchType), git2::Error>) -> Result<GitBr
     }
 }
 
-fn create_branch(repository: Repository, branch_name: &String) -> Result<(), git2::Error> {
-    let last_commit = find_last_commit(&repository)?;
-    repository.branch(branch_name, &last_commit, false)?;
-    let (object, reference) = repository.revparse_ext(branch_name)?;
-    match reference {
-        Some(gref) => repository.set_head(gref.name().unwrap())?,
-        None => repository.set_head_detached(object.id())?,
-    };
+// A start point is a branch name, a tag name or a commit id. Revision expressions such as
+// "master~1" are not accepted.
+pub fn find_start_point<'a>(repository: &'a Repository, start_point: &str) -> Option<Commit<'a>> {
+    for reference_name in [
+        format!("refs/heads/{}", start_point),
+        format!("refs/tags/{}", start_point),
+    ] {
+        if let Ok(commit) = repository
+            .find_reference(&reference_name)
+            .and_then(|r| r.peel_to_commit())
+        {
+            return Some(commit);
+        }
+    }
+    if start_point.len() < 4 || !start_point.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    repository
+        .revparse_single(start_point)
+        .and_then(|o| o.peel_to_commit())
+        .ok()
+}
+
+// The new branch is checked out, so the index and the working directory follow its start point
+// and not the previous HEAD.
+fn create_branch(
+    repository: &Repository,
+    branch_name: &str,
+    start_commit: &Commit,
+) -> Result<(), git2::Error> {
+    let branch = repository.branch(branch_name, start_commit, false)?;
+    repository.checkout_tree(
+        start_commit.as_object(),
+        Some(
+            git2::build::CheckoutBuilder::default()
+                .remove_untracked(true)
+                .remove_ignored(true)
+                .force(),
+        ),
+    )?;
+    repository.set_head(branch.get().name().unwrap())?;
     Ok(())
 }
 
-fn find_last_commit(repo: &Repository) -> Result<Commit, git2::Error> {
-    let obj = repo.head()?.resolve()?.peel(ObjectType::Commit)?;
-    obj.into_commit()
-        .map_err(|_| git2::Error::from_str("Couldn't find commit"))
+fn set_branch_metadata(
+    repository: &Repository,
+    branch_name: &str,
+    new_branch: &NewBranch,
+    base: &str,
+) -> Result<(), git2::Error> {
+    let mut config = repository.config()?;
+    let created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    if let Some(description) = &new_branch.description {
+        config.set_str(&format!("branch.{}.description", branch_name), description)?;
+    }
+    if let Some(creator) = &new_branch.creator {
+        config.set_str(&format!("branch.{}.creator", branch_name), creator)?;
+    }
+    config.set_str(&format!("branch.{}.base", branch_name), base)?;
+    config.set_str(
+        &format!("branch.{}.created", branch_name),
+        &created_at.to_string(),
+    )
 }
 
+// Only "refs/heads/{branch_name}" is checked out: tags, commit ids and revision expressions
+// never reach HEAD.
 fn set_branch(repository: &Repository, branch_name: &String) -> Result<(), git2::Error> {
-    let (object, reference) = repository.revparse_ext(branch_name)?;
-    match reference {
-        Some(gref) => repository.set_head(gref.name().unwrap())?,
-        None => repository.set_head_detached(object.id())?,
-    };
+    let branch = repository.find_branch(branch_name, BranchType::Local)?;
+    repository.set_head(branch.get().name().unwrap())?;
     repository.checkout_head(Some(
         git2::build::CheckoutBuilder::default()
         .remove_untracked(true).remove_ignored(true).force(),
@@ -201,10 +574,113 @@ fn set_branch(repository: &Repository, branch_name: &String) -> Result<(), git2:
     Ok(())
 }
 
-fn get_logs(repo: &Repository) -> Result<Vec<GitLog>, git2::Error> {
+// The default branch is stored in the workspace configuration when the workspace is created,
+// imported or forked. Older workspaces fall back to master, or to the branch of HEAD when there is
+// no master branch.
+pub fn get_default_branch(repository: &Repository) -> String {
+    if let Ok(default_branch) = repository
+        .config()
+        .and_then(|c| c.get_string("docversions.defaultbranch"))
+    {
+        return default_branch;
+    }
+    if repository.find_branch("master", BranchType::Local).is_err() {
+        if let Some(head_branch) = repository
+            .head()
+            .ok()
+            .filter(|h| h.is_branch())
+            .and_then(|h| h.shorthand().map(|s| s.to_string()))
+        {
+            return head_branch;
+        }
+    }
+    "master".to_string()
+}
+
+// A branch is merged when its tip is reachable from the current branch or from the default branch.
+fn is_merged(repository: &Repository, default_branch: &str, tip: Oid) -> Result<bool, git2::Error> {
+    let mut targets: Vec<Oid> = Vec::new();
+    if let Ok(head) = repository.head().and_then(|h| h.peel_to_commit()) {
+        targets.push(head.id());
+    }
+    if let Ok(branch) = repository.find_branch(default_branch, BranchType::Local) {
+        targets.push(branch.get().peel_to_commit()?.id());
+    }
+    for target in targets {
+        if target == tip || repository.graph_descendant_of(target, tip)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+// The files are compared between the merge base and the head branch, so only the changes made on
+// the head branch are listed.
+#[allow(clippy::type_complexity)]
+fn compare_commits(
+    repository: &Repository,
+    base: &Commit,
+    head: &Commit,
+) -> Result<(Option<Oid>, Vec<GitLog>, Vec<GitLog>, Vec<ChangedFile>), git2::Error> {
+    let merge_base = repository.merge_base(base.id(), head.id()).ok();
+    let ahead_commits = get_unique_commits(repository, head.id(), base.id())?;
+    let behind_commits = get_unique_commits(repository, base.id(), head.id())?;
+    let from_tree = match merge_base {
+        Some(oid) => repository.find_commit(oid)?.tree()?,
+        None => base.tree()?,
+    };
+    let diff = repository.diff_tree_to_tree(Some(&from_tree), Some(&head.tree()?), None)?;
+    let mut files = Vec::new();
+    for (index, delta) in diff.deltas().enumerate() {
+        let status = match delta.status() {
+            Delta::Added => "added",
+            Delta::Deleted => "deleted",
+            Delta::Renamed => "renamed",
+            _ => "modified",
+        };
+        let file = match delta.new_file().path().or(delta.old_file().path()) {
+            Some(p) => p.to_string_lossy().to_string(),
+            None => continue,
+        };
+        let (additions, deletions) = match Patch::from_diff(&diff, index)? {
+            Some(patch) => {
+                let (_, additions, deletions) = patch.line_stats()?;
+                (additions, deletions)
+            }
+            None => (0, 0),
+        };
+        files.push(ChangedFile {
+            name: file,
+            status: status.to_string(),
+            additions,
+            deletions,
+        });
+    }
+    Ok((merge_base, ahead_commits, behind_commits, files))
+}
+
+fn get_unique_commits(
+    repository: &Repository,
+    from: Oid,
+    hide: Oid,
+) -> Result<Vec<GitLog>, git2::Error> {
+    let mut revwalk = repository.revwalk()?;
+    revwalk.push(from)?;
+    revwalk.hide(hide)?;
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        let commit = repository.find_commit(oid?)?;
+        commits.push(GitLog {
+            commit_uuid: commit.id().to_string(),
+            message: commit.message().unwrap_or_default().to_string(),
+        });
+    }
+    Ok(commits)
+}
+
+fn get_logs(repo: &Repository, last_commit: &Commit) -> Result<Vec<GitLog>, git2::Error> {
     let mut vec: Vec<GitLog> = Vec::new();
-    let last_commit = find_last_commit(&repo)?;
-    get_commit_log(&repo, &last_commit, &mut vec)?;
+    get_commit_log(repo, last_commit, &mut vec)?;
     Ok(vec)
 }
 
@@ -0,0 +1,190 @@
+use crate::extractors::{BranchName, WorkspaceName};
+use crate::routes::{
+    check_direct_commits, check_rebase_in_progress, get_conflicts, update_branch, MergeConflict,
+};
+use actix_web::{web, HttpResponse};
+use git2::{BranchType, Commit, Oid, Repository, Signature};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct CherryPick {
+    pub commits: Vec<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct CherryPickedCommit {
+    source_commit_uuid: String,
+    commit_uuid: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct CherryPickResult {
+    commit_uuid: String,
+    commits: Vec<CherryPickedCommit>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct CherryPickConflicts {
+    source_commit_uuid: String,
+    branch: String,
+    conflicts: Vec<MergeConflict>,
+}
+
+// The commits are applied in the given order. The branch is only moved when every commit applies
+// cleanly, so a conflict leaves it untouched.
+// curl -X POST -H 'Content-Type: application/json' -d '{"commits":["commit_uuid"]}' -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}/cherry-pick
+pub async fn cherry_pick_commits(
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
+    cherry_pick: web::Json<CherryPick>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let workspace = PathBuf::from(workspace_path.as_str()).join(workspace_name.as_str());
+    let branch_name = branch_name.into_inner();
+    if cherry_pick.commits.is_empty() {
+        return HttpResponse::BadRequest().body("At least one commit must be given.");
+    }
+    let mut commit_ids = Vec::new();
+    for commit_uuid in &cherry_pick.commits {
+        match Oid::from_str(commit_uuid) {
+            Ok(oid) => commit_ids.push(oid),
+            Err(_) => {
+                return HttpResponse::BadRequest()
+                    .body(format!("The commit id {} is not valid.", commit_uuid))
+            }
+        }
+    }
+    let repository = match Repository::open(&workspace) {
+        Ok(r) => r,
+        Err(e) => {
+            println!("Error while retrieving the repository: {:#?}", e);
+            return HttpResponse::NotFound().finish();
+        }
+    };
+    if let Some(response) = check_rebase_in_progress(&repository) {
+        return response;
+    }
+    let mut tip = match repository
+        .find_branch(&branch_name, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+    {
+        Ok(c) => c,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    if let Some(response) = check_direct_commits(&repository, &branch_name) {
+        return response;
+    }
+    let mut commits = Vec::new();
+    for commit_id in commit_ids {
+        match repository.find_commit(commit_id) {
+            Ok(c) if c.parent_count() == 0 => {
+                return HttpResponse::BadRequest().body(format!(
+                    "The commit {} is the first commit of the history and cannot be cherry-picked.",
+                    commit_id
+                ))
+            }
+            Ok(c) => commits.push(c),
+            Err(_) => return HttpResponse::NotFound().finish(),
+        }
+    }
+
+    let mut cherry_picked_commits = Vec::new();
+    for commit in commits {
+        // Merge commits are applied against their first parent.
+        let mainline = if commit.parent_count() > 1 { 1 } else { 0 };
+        let mut index = match repository.cherrypick_commit(&commit, &tip, mainline, None) {
+            Ok(i) => i,
+            Err(e) => {
+                eprintln!(
+                    "Error while cherry-picking the commit {}: {:#?}",
+                    commit.id(),
+                    e
+                );
+                return HttpResponse::InternalServerError().finish();
+            }
+        };
+        if index.has_conflicts() {
+            return match get_conflicts(&index) {
+                Ok(conflicts) => HttpResponse::Conflict().json(CherryPickConflicts {
+                    source_commit_uuid: commit.id().to_string(),
+                    branch: branch_name,
+                    conflicts,
+                }),
+                Err(e) => {
+                    eprintln!("Error while retrieving the cherry-pick conflicts: {:#?}", e);
+                    HttpResponse::InternalServerError().finish()
+                }
+            };
+        }
+        let tree_id = match index.write_tree_to(&repository) {
+            Ok(oid) => oid,
+            Err(e) => {
+                eprintln!(
+                    "Error while cherry-picking the commit {}: {:#?}",
+                    commit.id(),
+                    e
+                );
+                return HttpResponse::InternalServerError().finish();
+            }
+        };
+        // A commit whose changes are already on the branch is skipped.
+        if tree_id == tip.tree_id() {
+            cherry_picked_commits.push(CherryPickedCommit {
+                source_commit_uuid: commit.id().to_string(),
+                commit_uuid: None,
+            });
+            continue;
+        }
+        tip = match create_cherry_pick_commit(&repository, tree_id, &tip, &commit) {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!(
+                    "Error while cherry-picking the commit {}: {:#?}",
+                    commit.id(),
+                    e
+                );
+                return HttpResponse::InternalServerError().finish();
+            }
+        };
+        cherry_picked_commits.push(CherryPickedCommit {
+            source_commit_uuid: commit.id().to_string(),
+            commit_uuid: Some(tip.id().to_string()),
+        });
+    }
+    match update_branch(&repository, &branch_name, tip.id(), "cherry-pick") {
+        Ok(()) => HttpResponse::Ok().json(CherryPickResult {
+            commit_uuid: tip.id().to_string(),
+            commits: cherry_picked_commits,
+        }),
+        Err(e) => {
+            eprintln!("Error while updating the branch {}: {:#?}", &branch_name, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+// The original author and message are kept, followed by the trailer that "git cherry-pick -x"
+// writes.
+fn create_cherry_pick_commit<'a>(
+    repository: &'a Repository,
+    tree_id: Oid,
+    parent: &Commit,
+    commit: &Commit,
+) -> Result<Commit<'a>, git2::Error> {
+    let tree = repository.find_tree(tree_id)?;
+    let committer = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    let message = format!(
+        "{}\n\n(cherry picked from commit {})\n",
+        commit.message().unwrap_or_default().trim_end(),
+        commit.id()
+    );
+    let oid = repository.commit(
+        None,
+        &commit.author(),
+        &committer,
+        &message,
+        &tree,
+        &[parent],
+    )?;
+    repository.find_commit(oid)
+}
@@ -0,0 +1,457 @@
+use crate::extractors::{FilePath, WorkspaceName};
+use crate::routes::{
+    check_rebase_in_progress, create_merge_commit, get_conflicts, update_branch, ConflictKind,
+};
+use actix_multipart::form::tempfile::TempFile;
+use actix_multipart::form::MultipartForm;
+use actix_web::{web, HttpResponse};
+use git2::{BranchType, Index, IndexEntry, IndexTime, Oid, Repository};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MERGE_SESSIONS_DIRECTORY: &str = "docversions/merges";
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct MergeSession {
+    pub id: String,
+    pub source: String,
+    pub target: String,
+    pub source_commit: String,
+    pub target_commit: String,
+    pub message: String,
+    pub created_at: u64,
+    pub files: Vec<ConflictFile>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ConflictFile {
+    pub path: String,
+    pub kind: ConflictKind,
+    pub mode: u32,
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+    pub resolution: Option<Resolution>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Resolution {
+    Content { blob: String },
+    Delete,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct ConflictFileContent {
+    path: String,
+    kind: ConflictKind,
+    base: Option<String>,
+    ours: Option<String>,
+    theirs: Option<String>,
+    resolved: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct MergeSessionCommit {
+    commit_uuid: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct MergeSessionPath {
+    pub session_id: String,
+}
+
+#[derive(MultipartForm)]
+pub struct ResolveFile {
+    pub file: TempFile,
+}
+
+// curl -X GET -v http://127.0.0.1:8000/workspaces/{workspace_name}/merges
+pub async fn retrieve_merge_sessions(
+    workspace_name: WorkspaceName,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let repository = match get_repository(&workspace_path, &workspace_name) {
+        Some(r) => r,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    match get_merge_sessions(&repository) {
+        Ok(sessions) => HttpResponse::Ok().json(sessions),
+        Err(e) => {
+            eprintln!("Error while retrieving the merge sessions: {:#?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+// curl -X GET -v http://127.0.0.1:8000/workspaces/{workspace_name}/merges/{session_id}
+pub async fn retrieve_merge_session(
+    workspace_name: WorkspaceName,
+    session_path: web::Path<MergeSessionPath>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let session_id = &session_path.session_id;
+    let repository = match get_repository(&workspace_path, &workspace_name) {
+        Some(r) => r,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    match load_merge_session(&repository, session_id) {
+        Some(session) => HttpResponse::Ok().json(session),
+        None => HttpResponse::NotFound().finish(),
+    }
+}
+
+// Files inside directories are addressed with the "path" query parameter.
+// curl -X GET -v http://127.0.0.1:8000/workspaces/{workspace_name}/merges/{session_id}/files/{file_name}
+// curl -X GET -v "http://127.0.0.1:8000/workspaces/{workspace_name}/merges/{session_id}/files?path={file_path}"
+pub async fn retrieve_conflict_file(
+    workspace_name: WorkspaceName,
+    session_path: web::Path<MergeSessionPath>,
+    file_path: FilePath,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let session_id = &session_path.session_id;
+    let repository = match get_repository(&workspace_path, &workspace_name) {
+        Some(r) => r,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    let session = match load_merge_session(&repository, session_id) {
+        Some(s) => s,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    let file = match session.files.iter().find(|f| f.path == file_path.as_str()) {
+        Some(f) => f,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    match get_conflict_file_content(&repository, file) {
+        Ok(c) => HttpResponse::Ok().json(c),
+        Err(e) => {
+            eprintln!("Error while reading the conflicted file: {:#?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+// curl -X PUT -H 'Content-Type: multipart/form-data' -F file=@/path/to/file -v http://127.0.0.1:8000/workspaces/{workspace_name}/merges/{session_id}/files/{file_name}
+pub async fn resolve_conflict_file(
+    workspace_name: WorkspaceName,
+    session_path: web::Path<MergeSessionPath>,
+    file_path: FilePath,
+    form: MultipartForm<ResolveFile>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let repository = match get_repository(&workspace_path, &workspace_name) {
+        Some(r) => r,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    let blob = match fs::read(form.file.file.path())
+        .map_err(|e| git2::Error::from_str(&e.to_string()))
+        .and_then(|content| repository.blob(&content))
+    {
+        Ok(b) => b,
+        Err(e) => {
+            eprintln!("Error while storing the resolved file: {:#?}", e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    set_resolution(
+        &repository,
+        &session_path.session_id,
+        &file_path,
+        Resolution::Content {
+            blob: blob.to_string(),
+        },
+    )
+}
+
+// curl -X DELETE -v http://127.0.0.1:8000/workspaces/{workspace_name}/merges/{session_id}/files/{file_name}
+pub async fn resolve_conflict_file_as_deleted(
+    workspace_name: WorkspaceName,
+    session_path: web::Path<MergeSessionPath>,
+    file_path: FilePath,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let repository = match get_repository(&workspace_path, &workspace_name) {
+        Some(r) => r,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    set_resolution(
+        &repository,
+        &session_path.session_id,
+        &file_path,
+        Resolution::Delete,
+    )
+}
+
+// curl -X POST -v http://127.0.0.1:8000/workspaces/{workspace_name}/merges/{session_id}/commit
+pub async fn commit_merge_session(
+    workspace_name: WorkspaceName,
+    session_path: web::Path<MergeSessionPath>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let session_id = &session_path.session_id;
+    let repository = match get_repository(&workspace_path, &workspace_name) {
+        Some(r) => r,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    if let Some(response) = check_rebase_in_progress(&repository) {
+        return response;
+    }
+    let session = match load_merge_session(&repository, session_id) {
+        Some(s) => s,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    let unresolved: Vec<&str> = session
+        .files
+        .iter()
+        .filter(|f| f.resolution.is_none())
+        .map(|f| f.path.as_str())
+        .collect();
+    if !unresolved.is_empty() {
+        return HttpResponse::Conflict().body(format!(
+            "The files {} are not resolved.",
+            unresolved.join(", ")
+        ));
+    }
+    let target_tip = repository
+        .find_branch(&session.target, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .map(|c| c.id().to_string());
+    if target_tip.ok().as_ref() != Some(&session.target_commit) {
+        return HttpResponse::Conflict().body(format!(
+            "The branch {} has changed since the merge started.",
+            &session.target
+        ));
+    }
+
+    match commit_resolved_merge(&repository, &session) {
+        Ok(oid) => {
+            if let Err(e) = fs::remove_file(get_merge_session_file(&repository, session_id)) {
+                eprintln!("Error while removing the merge session: {:#?}", e);
+            }
+            HttpResponse::Ok().json(MergeSessionCommit {
+                commit_uuid: oid.to_string(),
+            })
+        }
+        Err(e) => {
+            eprintln!("Error while committing the merge: {:#?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+// curl -X DELETE -v http://127.0.0.1:8000/workspaces/{workspace_name}/merges/{session_id}
+pub async fn abort_merge_session(
+    workspace_name: WorkspaceName,
+    session_path: web::Path<MergeSessionPath>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let session_id = &session_path.session_id;
+    let repository = match get_repository(&workspace_path, &workspace_name) {
+        Some(r) => r,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    if load_merge_session(&repository, session_id).is_none() {
+        return HttpResponse::NotFound().finish();
+    }
+    if let Err(e) = fs::remove_file(get_merge_session_file(&repository, session_id)) {
+        eprintln!("Error while aborting the merge: {:#?}", e);
+        return HttpResponse::InternalServerError().finish();
+    }
+    HttpResponse::Ok().finish()
+}
+
+// Sessions are stored as JSON files inside the .git directory, so they survive a restart and move
+// together with the workspace when it is renamed or trashed.
+pub fn create_merge_session(
+    repository: &Repository,
+    source: &str,
+    target: &str,
+    source_commit: Oid,
+    target_commit: Oid,
+    message: &str,
+    index: &Index,
+) -> Result<MergeSession, Box<dyn std::error::Error>> {
+    let mut files = Vec::new();
+    let conflicts = get_conflicts(index)?;
+    for (conflict, entries) in conflicts.into_iter().zip(index.conflicts()?) {
+        let entries = entries?;
+        let mode = entries
+            .our
+            .as_ref()
+            .or(entries.their.as_ref())
+            .or(entries.ancestor.as_ref())
+            .map(|e| e.mode)
+            .unwrap_or(0o100644);
+        files.push(ConflictFile {
+            path: conflict.path,
+            kind: conflict.kind,
+            mode,
+            base: entries.ancestor.map(|e| e.id.to_string()),
+            ours: entries.our.map(|e| e.id.to_string()),
+            theirs: entries.their.map(|e| e.id.to_string()),
+            resolution: None,
+        });
+    }
+    let created_at = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let mut nanos = created_at.as_nanos();
+    while get_merge_session_file(repository, &format!("{:x}", nanos)).exists() {
+        nanos += 1;
+    }
+    let id = format!("{:x}", nanos);
+    let session = MergeSession {
+        id,
+        source: source.to_string(),
+        target: target.to_string(),
+        source_commit: source_commit.to_string(),
+        target_commit: target_commit.to_string(),
+        message: message.to_string(),
+        created_at: created_at.as_secs(),
+        files,
+    };
+    save_merge_session(repository, &session)?;
+    Ok(session)
+}
+
+fn get_repository(workspace_path: &str, workspace_name: &str) -> Option<Repository> {
+    let workspace = PathBuf::from(workspace_path).join(workspace_name);
+    match Repository::open(workspace) {
+        Ok(r) => Some(r),
+        Err(e) => {
+            println!("{}", &e.message());
+            None
+        }
+    }
+}
+
+fn get_merge_sessions_directory(repository: &Repository) -> PathBuf {
+    repository.path().join(MERGE_SESSIONS_DIRECTORY)
+}
+
+fn get_merge_session_file(repository: &Repository, session_id: &str) -> PathBuf {
+    get_merge_sessions_directory(repository).join(format!("{}.json", session_id))
+}
+
+fn get_merge_sessions(repository: &Repository) -> std::io::Result<Vec<MergeSession>> {
+    let directory = get_merge_sessions_directory(repository);
+    if !directory.exists() {
+        return Ok(Vec::new());
+    }
+    let mut sessions: Vec<MergeSession> = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        if let Some(session) = read_merge_session(&entry?.path()) {
+            sessions.push(session);
+        }
+    }
+    sessions.sort_by_key(|s| s.created_at);
+    Ok(sessions)
+}
+
+fn load_merge_session(repository: &Repository, session_id: &str) -> Option<MergeSession> {
+    if session_id.is_empty() || !session_id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    read_merge_session(&get_merge_session_file(repository, session_id))
+}
+
+fn read_merge_session(session_file: &Path) -> Option<MergeSession> {
+    let content = fs::read(session_file).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+fn save_merge_session(
+    repository: &Repository,
+    session: &MergeSession,
+) -> Result<(), Box<dyn std::error::Error>> {
+    fs::create_dir_all(get_merge_sessions_directory(repository))?;
+    fs::write(
+        get_merge_session_file(repository, &session.id),
+        serde_json::to_vec_pretty(session)?,
+    )?;
+    Ok(())
+}
+
+fn set_resolution(
+    repository: &Repository,
+    session_id: &str,
+    file_path: &str,
+    resolution: Resolution,
+) -> HttpResponse {
+    let mut session = match load_merge_session(repository, session_id) {
+        Some(s) => s,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    match session.files.iter_mut().find(|f| f.path == file_path) {
+        Some(f) => f.resolution = Some(resolution),
+        None => return HttpResponse::NotFound().finish(),
+    };
+    if let Err(e) = save_merge_session(repository, &session) {
+        eprintln!("Error while saving the merge session: {:#?}", e);
+        return HttpResponse::InternalServerError().finish();
+    }
+    HttpResponse::Ok().json(session)
+}
+
+fn get_conflict_file_content(
+    repository: &Repository,
+    file: &ConflictFile,
+) -> Result<ConflictFileContent, git2::Error> {
+    let resolved = match &file.resolution {
+        Some(Resolution::Content { blob }) => Some(blob.clone()),
+        _ => None,
+    };
+    Ok(ConflictFileContent {
+        path: file.path.clone(),
+        kind: file.kind,
+        base: read_blob(repository, &file.base)?,
+        ours: read_blob(repository, &file.ours)?,
+        theirs: read_blob(repository, &file.theirs)?,
+        resolved: read_blob(repository, &resolved)?,
+    })
+}
+
+fn read_blob(repository: &Repository, oid: &Option<String>) -> Result<Option<String>, git2::Error> {
+    match oid {
+        Some(oid) => {
+            let blob = repository.find_blob(Oid::from_str(oid)?)?;
+            Ok(Some(String::from_utf8_lossy(blob.content()).to_string()))
+        }
+        None => Ok(None),
+    }
+}
+
+// Replays the merge of the two recorded commits and replaces every conflict with its resolution.
+fn commit_resolved_merge(
+    repository: &Repository,
+    session: &MergeSession,
+) -> Result<Oid, git2::Error> {
+    let target = repository.find_commit(Oid::from_str(&session.target_commit)?)?;
+    let source = repository.find_commit(Oid::from_str(&session.source_commit)?)?;
+    let mut index = repository.merge_commits(&target, &source, None)?;
+    for file in &session.files {
+        let path = Path::new(&file.path);
+        index.remove_path(path)?;
+        if let Some(Resolution::Content { blob }) = &file.resolution {
+            let oid = Oid::from_str(blob)?;
+            let size = repository.find_blob(oid)?.content().len() as u32;
+            index.add(&IndexEntry {
+                ctime: IndexTime::new(0, 0),
+                mtime: IndexTime::new(0, 0),
+                dev: 0,
+                ino: 0,
+                mode: file.mode,
+                uid: 0,
+                gid: 0,
+                file_size: size,
+                id: oid,
+                flags: file.path.len().min(0xfff) as u16,
+                flags_extended: 0,
+                path: file.path.as_bytes().to_vec(),
+            })?;
+        }
+    }
+    let oid = create_merge_commit(repository, &mut index, &target, &source, &session.message)?;
+    update_branch(repository, &session.target, oid, "merge")?;
+    Ok(oid)
+}
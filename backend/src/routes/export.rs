@@ -0,0 +1,240 @@
+use crate::extractors::{BranchName, WorkspaceName};
+use crate::routes::{find_branch_or_tag_commit, get_default_branch};
+use actix_web::body::{BodySize, MessageBody};
+use actix_web::http::header;
+use actix_web::web::Bytes;
+use actix_web::{web, HttpResponse};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use git2::{ObjectType, Oid, Repository, TreeWalkMode, TreeWalkResult};
+use std::error::Error;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, Default, serde::Deserialize, serde::Serialize)]
+pub enum ExportFormat {
+    #[default]
+    #[serde(rename = "bundle")]
+    Bundle,
+    #[serde(rename = "tar.gz")]
+    TarGz,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+    pub branch: Option<String>,
+}
+
+// The export is streamed while it is written, so an error after the response has started can only
+// interrupt the body.
+// curl -X GET -v http://127.0.0.1:8000/workspaces/{workspace_name}/export?format=bundle -o workspace.bundle
+// curl -X GET -v "http://127.0.0.1:8000/workspaces/{workspace_name}/export?format=tar.gz&branch={branch_name|tag_name}" -o workspace.tar.gz
+pub async fn export_workspace(
+    workspace_name: WorkspaceName,
+    query: web::Query<ExportQuery>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let workspace_name = workspace_name.into_inner();
+    let workspace_directory = PathBuf::from(&workspace_path.as_str());
+    let workspace = get_workspace(&workspace_name, &workspace_directory);
+
+    if !workspace.exists() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let repository = match Repository::open(&workspace) {
+        Ok(r) => r,
+        Err(e) => {
+            println!("Error while retrieving the repository: {:#?}", e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    match query.format {
+        ExportFormat::Bundle => HttpResponse::Ok()
+            .content_type("application/x-git-bundle")
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}.bundle\"", &workspace_name),
+            ))
+            .body(stream_export(move |writer| {
+                create_bundle(&repository, writer)
+            })),
+        ExportFormat::TarGz => {
+            let branch_name = match &query.branch {
+                Some(b) => b.clone(),
+                None => get_default_branch(&repository),
+            };
+            let branch_name = match BranchName::parse(&branch_name) {
+                Ok(b) => b,
+                Err(reason) => return HttpResponse::BadRequest().body(reason),
+            };
+            let commit_id = match find_branch_or_tag_commit(&repository, &branch_name) {
+                Ok(c) => c.id(),
+                Err(e) => {
+                    eprintln!(
+                        "Error while retrieving the branch {}: {:#?}",
+                        &branch_name, e
+                    );
+                    return HttpResponse::NotFound().finish();
+                }
+            };
+            let disposition = format!(
+                "attachment; filename=\"{}-{}.tar.gz\"",
+                &workspace_name,
+                branch_name.replace('/', "-")
+            );
+            HttpResponse::Ok()
+                .content_type("application/gzip")
+                .insert_header((header::CONTENT_DISPOSITION, disposition))
+                .body(stream_export(move |writer| {
+                    create_archive(&repository, commit_id, &workspace_name, writer)
+                }))
+        }
+    }
+}
+
+fn get_workspace(workspace_name: &str, workspace_directory: &PathBuf) -> PathBuf {
+    let mut workspace = PathBuf::from(&workspace_directory);
+    workspace.push(workspace_name);
+    workspace
+}
+
+// The export is written by a blocking task into a bounded channel, and the response body forwards
+// the chunks as they arrive, so the export is never held in memory as a whole.
+fn stream_export<F>(export: F) -> ExportBody
+where
+    F: FnOnce(&mut dyn Write) -> Result<(), Box<dyn Error>> + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel(16);
+    tokio::task::spawn_blocking(move || {
+        let mut writer = BufWriter::with_capacity(CHUNK_SIZE, ExportWriter(sender.clone()));
+        let result = export(&mut writer).and_then(|_| Ok(writer.flush()?));
+        if let Err(e) = result {
+            eprintln!("Error while exporting the workspace: {:#?}", e);
+            let _ = sender.blocking_send(Err(io::Error::other(e.to_string())));
+        }
+    });
+    ExportBody(receiver)
+}
+
+struct ExportWriter(mpsc::Sender<io::Result<Bytes>>);
+
+impl Write for ExportWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .blocking_send(Ok(Bytes::copy_from_slice(buf)))
+            .map_err(|_| {
+                io::Error::new(io::ErrorKind::BrokenPipe, "The export was interrupted.")
+            })?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct ExportBody(mpsc::Receiver<io::Result<Bytes>>);
+
+impl MessageBody for ExportBody {
+    type Error = io::Error;
+
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        self.0.poll_recv(cx)
+    }
+}
+
+// Git bundle v2: a header line, one "<oid> <refname>" line per reference, an empty line and a
+// packfile with every object reachable from the listed references.
+fn create_bundle(repository: &Repository, writer: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+    let mut header = b"# v2 git bundle\n".to_vec();
+    let mut revwalk = repository.revwalk()?;
+    let mut tags = Vec::new();
+    for reference in repository.references()? {
+        let reference = reference?;
+        let name = match reference.name() {
+            Some(n) if n.starts_with("refs/heads/") || n.starts_with("refs/tags/") => n,
+            _ => continue,
+        };
+        let oid = match reference.target() {
+            Some(oid) => oid,
+            None => continue,
+        };
+        header.extend_from_slice(format!("{} {}\n", oid, name).as_bytes());
+        if reference.is_tag() {
+            tags.push(oid);
+        }
+        revwalk.push(reference.peel(ObjectType::Commit)?.id())?;
+    }
+    if let Ok(head) = repository.head() {
+        if let Some(oid) = head.target() {
+            header.extend_from_slice(format!("{} HEAD\n", oid).as_bytes());
+        }
+    }
+    header.push(b'\n');
+    writer.write_all(&header)?;
+
+    let mut pack_builder = repository.packbuilder()?;
+    pack_builder.insert_walk(&mut revwalk)?;
+    for tag in tags {
+        pack_builder.insert_object(tag, None)?;
+    }
+    let mut write_error = None;
+    let written = pack_builder.foreach(|chunk| match writer.write_all(chunk) {
+        Ok(()) => true,
+        Err(e) => {
+            write_error = Some(e);
+            false
+        }
+    });
+    if let Some(e) = write_error {
+        return Err(e.into());
+    }
+    Ok(written?)
+}
+
+fn create_archive(
+    repository: &Repository,
+    commit_id: Oid,
+    prefix: &str,
+    writer: &mut dyn Write,
+) -> Result<(), Box<dyn Error>> {
+    let commit = repository.find_commit(commit_id)?;
+    let tree = commit.tree()?;
+    let mut entries = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+        if entry.kind() == Some(ObjectType::Blob) {
+            let path = format!("{}/{}{}", prefix, root, entry.name().unwrap_or_default());
+            entries.push((path, entry.id(), entry.filemode()));
+        }
+        TreeWalkResult::Ok
+    })?;
+
+    let mtime = commit.time().seconds().max(0) as u64;
+    let mut archive = tar::Builder::new(GzEncoder::new(writer, Compression::default()));
+    for (path, oid, filemode) in entries {
+        let blob = repository.find_blob(oid)?;
+        let mut header = tar::Header::new_gnu();
+        header.set_size(blob.content().len() as u64);
+        header.set_mode(if filemode == 0o100755 { 0o755 } else { 0o644 });
+        header.set_mtime(mtime);
+        archive.append_data(&mut header, path, blob.content())?;
+    }
+    archive.into_inner()?.finish()?;
+    Ok(())
+}
@@ -1,8 +1,16 @@
+use crate::configuration::Settings;
+use crate::extractors::{BranchName, FileName, FilePath, WorkspaceName};
+use crate::routes::{
+    check_direct_commits, check_rebase_in_progress, check_upload_quota, find_branch_or_tag_commit,
+};
 use actix_multipart::form::tempfile::TempFile;
 use actix_multipart::form::text::Text;
 use actix_multipart::form::MultipartForm;
 use actix_web::{web, HttpResponse};
-use git2::{Commit, IndexAddOption, ObjectType, Repository, Signature, Status, StatusOptions};
+use git2::{
+    BranchType, Commit, ErrorCode, IndexAddOption, ObjectType, Repository, Signature, Status,
+    StatusOptions, TreeWalkMode, TreeWalkResult,
+};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -23,15 +31,16 @@ pub struct Delete {
     pub commit_message: Text<String>,
 }
 
-// curl -X GET -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}/files
+// curl -X GET -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name|tag_name}/files
 pub async fn retrieve_files_status(
-    path_param: web::Path<(String, String)>,
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
     workspace_path: web::Data<String>,
 ) -> HttpResponse {
-    let workspace_name = &path_param.0;
+    let workspace_name = &workspace_name.into_inner();
     let workspace_directory = PathBuf::from(&workspace_path.as_str());
     let workspace_path = get_workspace_path(workspace_directory, &workspace_name);
-    let branch_name = &path_param.1;
+    let branch_name = &branch_name.into_inner();
     let repository = match get_repository(&workspace_path) {
         Some(r) => r,
         None => {
@@ -39,14 +48,18 @@ pub async fn retrieve_files_status(
             return HttpResponse::InternalServerError().finish();
         }
     };
-    if let Err(e) = set_branch(&repository, branch_name) {
-        eprintln!(
-            "Error while setting the current branch {}: {:#?}",
-            branch_name, e
-        );
-        return HttpResponse::InternalServerError().finish();
-    }
-    match get_files_status_from_last_commit(&repository) {
+    let commit = match find_branch_or_tag_commit(&repository, branch_name) {
+        Ok(c) => c,
+        Err(e) if e.code() == ErrorCode::NotFound => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            eprintln!(
+                "Error while retrieving the last commit of the branch {}: {:#?}",
+                branch_name, e
+            );
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    match get_files_status(&repository, branch_name, &commit) {
         Ok(files_status) => HttpResponse::Ok().json(files_status),
         Err(e) => {
             eprintln!(
@@ -58,15 +71,18 @@ pub async fn retrieve_files_status(
     }
 }
 
-// curl -X GET -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}/files/{file_name}
+// Files inside directories are read with their path, e.g. ".../files/chapters/one.md".
+// curl -X GET -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name|tag_name}/files/{file_path}
 pub async fn retrieve_file_content(
-    path_param: web::Path<(String, String, String)>,
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
+    file_path: FilePath,
     workspace_path: web::Data<String>,
 ) -> HttpResponse {
-    let workspace_name = &path_param.0;
+    let workspace_name = &workspace_name.into_inner();
     let workspace_directory = PathBuf::from(&workspace_path.as_str());
     let workspace_path = get_workspace_path(workspace_directory, &workspace_name);
-    let branch_name = &path_param.1;
+    let branch_name = &branch_name.into_inner();
     let repository = match get_repository(&workspace_path) {
         Some(r) => r,
         None => {
@@ -74,16 +90,20 @@ pub async fn retrieve_file_content(
             return HttpResponse::InternalServerError().finish();
         }
     };
-    if let Err(e) = set_branch(&repository, branch_name) {
-        eprintln!(
-            "Error while setting the current branch {}: {:#?}",
-            branch_name, e
-        );
-        return HttpResponse::InternalServerError().finish();
-    }
-    let file_name = &path_param.2;
-    match get_file_content_from_last_commit(&repository, &file_name) {
+    let commit = match find_branch_or_tag_commit(&repository, branch_name) {
+        Ok(c) => c,
+        Err(e) if e.code() == ErrorCode::NotFound => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            eprintln!(
+                "Error while retrieving the last commit of the branch {}: {:#?}",
+                branch_name, e
+            );
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    match get_file_content_from_commit(&repository, &commit, &file_path) {
         Ok(file_content) => HttpResponse::Ok().body(file_content),
+        Err(e) if e.code() == ErrorCode::NotFound => HttpResponse::NotFound().finish(),
         Err(e) => {
             eprintln!(
                 "Error while retrieving the file content from the branch {}: {:#?}",
@@ -96,14 +116,17 @@ pub async fn retrieve_file_content(
 
 // curl -X POST -H 'Content-Type: multipart/form-data' -F file=@/path/to/file -Fcommit_message='commit message' -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}/files/{file_name}
 pub async fn create_file(
-    path_param: web::Path<(String, String, String)>,
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
+    file_name: FileName,
     form: MultipartForm<Upload>,
     workspace_path: web::Data<String>,
+    settings: web::Data<Settings>,
 ) -> HttpResponse {
-    let workspace_name = &path_param.0;
+    let workspace_name = &workspace_name.into_inner();
     let workspace_directory = PathBuf::from(&workspace_path.as_str());
     let workspace_path = get_workspace_path(workspace_directory, &workspace_name);
-    let branch_name = &path_param.1;
+    let branch_name = &branch_name.into_inner();
     let repository = match get_repository(&workspace_path) {
         Some(r) => r,
         None => {
@@ -111,6 +134,19 @@ pub async fn create_file(
             return HttpResponse::InternalServerError().finish();
         }
     };
+    if let Some(response) = check_rebase_in_progress(&repository) {
+        return response;
+    }
+    // The protection rules and the write must address the same local branch.
+    if repository
+        .find_branch(branch_name, BranchType::Local)
+        .is_err()
+    {
+        return HttpResponse::NotFound().finish();
+    }
+    if let Some(response) = check_direct_commits(&repository, branch_name) {
+        return response;
+    }
     if let Err(e) = set_branch(&repository, branch_name) {
         eprintln!(
             "Error while setting the current branch {}: {:#?}",
@@ -118,7 +154,7 @@ pub async fn create_file(
         );
         return HttpResponse::InternalServerError().finish();
     }
-    let file_name = &path_param.2;
+    let file_name = &file_name.into_inner();
     match get_files_status_from_last_commit(&repository) {
         Ok(files_status) => {
             let files_name: Vec<String> = files_status
@@ -139,6 +175,16 @@ pub async fn create_file(
     }
     let source_filepath = &form.file.file.as_ref();
     let destination_filepath = Path::new(&workspace_path).join(&file_name);
+    let replaced_size = fs::metadata(&destination_filepath).map(|m| m.len()).ok();
+    if let Err(e) = check_upload_quota(
+        Path::new(&workspace_path),
+        settings.quotas.get_limits(workspace_name),
+        form.file.size as u64,
+        replaced_size,
+    ) {
+        eprintln!("Error while creating the file: {}", e);
+        return e.to_response();
+    }
     if let Err(e) = fs::copy(&source_filepath, &destination_filepath) {
         eprintln!("Error while creating the file: {:#?}", e);
         return HttpResponse::InternalServerError().finish();
@@ -156,14 +202,17 @@ pub async fn create_file(
 
 // curl -X PUT -H 'Content-Type: multipart/form-data' -F file=@/path/to/file -Fcommit_message='commit message' -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}/files/{file_name}
 pub async fn update_file(
-    path_param: web::Path<(String, String, String)>,
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
+    file_name: FileName,
     form: MultipartForm<Upload>,
     workspace_path: web::Data<String>,
+    settings: web::Data<Settings>,
 ) -> HttpResponse {
-    let workspace_name = &path_param.0;
+    let workspace_name = &workspace_name.into_inner();
     let workspace_directory = PathBuf::from(&workspace_path.as_str());
     let workspace_path = get_workspace_path(workspace_directory, &workspace_name);
-    let branch_name = &path_param.1;
+    let branch_name = &branch_name.into_inner();
     let repository = match get_repository(&workspace_path) {
         Some(r) => r,
         None => {
@@ -171,6 +220,18 @@ pub async fn update_file(
             return HttpResponse::InternalServerError().finish();
         }
     };
+    if let Some(response) = check_rebase_in_progress(&repository) {
+        return response;
+    }
+    if repository
+        .find_branch(branch_name, BranchType::Local)
+        .is_err()
+    {
+        return HttpResponse::NotFound().finish();
+    }
+    if let Some(response) = check_direct_commits(&repository, branch_name) {
+        return response;
+    }
     if let Err(e) = set_branch(&repository, branch_name) {
         eprintln!(
             "Error while setting the current branch {}: {:#?}",
@@ -178,7 +239,7 @@ pub async fn update_file(
         );
         return HttpResponse::InternalServerError().finish();
     }
-    let file_name = &path_param.2;
+    let file_name = &file_name.into_inner();
     match get_files_status_from_last_commit(&repository) {
         Ok(files_status) => {
             let files_name: Vec<String> = files_status
@@ -199,6 +260,16 @@ pub async fn update_file(
     }
     let source_filepath = &form.file.file.as_ref();
     let destination_filepath = Path::new(&workspace_path).join(&file_name);
+    let replaced_size = fs::metadata(&destination_filepath).map(|m| m.len()).ok();
+    if let Err(e) = check_upload_quota(
+        Path::new(&workspace_path),
+        settings.quotas.get_limits(workspace_name),
+        form.file.size as u64,
+        replaced_size,
+    ) {
+        eprintln!("Error while updating the file: {}", e);
+        return e.to_response();
+    }
     if let Err(e) = fs::copy(&source_filepath, &destination_filepath) {
         eprintln!("Error while updating the file: {:#?}", e);
         return HttpResponse::InternalServerError().finish();
@@ -216,14 +287,16 @@ pub async fn update_file(
 
 // curl -X DELETE -H 'Content-Type: multipart/form-data' -Fcommit_message='commit message' -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}/files/{file_name}
 pub async fn delete_file(
-    path_param: web::Path<(String, String, String)>,
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
+    file_name: FileName,
     form: MultipartForm<Delete>,
     workspace_path: web::Data<String>,
 ) -> HttpResponse {
-    let workspace_name = &path_param.0;
+    let workspace_name = &workspace_name.into_inner();
     let workspace_directory = PathBuf::from(&workspace_path.as_str());
     let workspace_path = get_workspace_path(workspace_directory, &workspace_name);
-    let branch_name = &path_param.1;
+    let branch_name = &branch_name.into_inner();
     let repository = match get_repository(&workspace_path) {
         Some(r) => r,
         None => {
@@ -231,6 +304,18 @@ pub async fn delete_file(
             return HttpResponse::InternalServerError().finish();
         }
     };
+    if let Some(response) = check_rebase_in_progress(&repository) {
+        return response;
+    }
+    if repository
+        .find_branch(branch_name, BranchType::Local)
+        .is_err()
+    {
+        return HttpResponse::NotFound().finish();
+    }
+    if let Some(response) = check_direct_commits(&repository, branch_name) {
+        return response;
+    }
     if let Err(e) = set_branch(&repository, branch_name) {
         eprintln!(
             "Error while setting the current branch {}: {:#?}",
@@ -238,7 +323,7 @@ pub async fn delete_file(
         );
         return HttpResponse::InternalServerError().finish();
     }
-    let file_name = &path_param.2;
+    let file_name = &file_name.into_inner();
     match get_files_status_from_last_commit(&repository) {
         Ok(files_status) => {
             let files_name: Vec<String> = files_status
@@ -291,12 +376,11 @@ fn get_repository(workspace_path: &str) -> Option<Repository> {
     Some(repository)
 }
 
+// Only "refs/heads/{branch_name}" is checked out: tags, commit ids and revision expressions
+// never reach HEAD.
 fn set_branch(repository: &Repository, branch_name: &String) -> Result<(), git2::Error> {
-    let (object, reference) = repository.revparse_ext(branch_name)?;
-    match reference {
-        Some(gref) => repository.set_head(gref.name().unwrap())?,
-        None => repository.set_head_detached(object.id())?,
-    };
+    let branch = repository.find_branch(branch_name, BranchType::Local)?;
+    repository.set_head(branch.get().name().unwrap())?;
     repository.checkout_head(Some(
         git2::build::CheckoutBuilder::default()
         .remove_untracked(true).remove_ignored(true).force(),
@@ -304,6 +388,20 @@ fn set_branch(repository: &Repository, branch_name: &String) -> Result<(), git2:
     Ok(())
 }
 
+// The checked out branch reports the status of the working directory, while the files of any other
+// branch or tag are read from its commit.
+fn get_files_status(
+    repository: &Repository,
+    name: &str,
+    commit: &Commit,
+) -> Result<Vec<FileStatus>, git2::Error> {
+    let head = repository.head().ok();
+    if head.as_ref().and_then(|h| h.name()) == Some(format!("refs/heads/{}", name).as_str()) {
+        return get_files_status_from_last_commit(repository);
+    }
+    get_files_status_from_commit(commit)
+}
+
 fn get_files_status_from_last_commit(repo: &Repository) -> Result<Vec<FileStatus>, git2::Error> {
     let mut vec: Vec<FileStatus> = Vec::new();
     let mut status_options = StatusOptions::new();
@@ -328,33 +426,41 @@ fn get_files_status_from_last_commit(repo: &Repository) -> Result<Vec<FileStatus
     Ok(vec)
 }
 
+// Every file of a commit is committed, so its status is always "current".
+fn get_files_status_from_commit(commit: &Commit) -> Result<Vec<FileStatus>, git2::Error> {
+    let mut vec: Vec<FileStatus> = Vec::new();
+    commit.tree()?.walk(TreeWalkMode::PreOrder, |root, entry| {
+        if entry.kind() == Some(ObjectType::Blob) {
+            vec.push(FileStatus {
+                name: format!("{}{}", root, entry.name().unwrap_or_default()),
+                status: "current".to_string(),
+            });
+        }
+        TreeWalkResult::Ok
+    })?;
+    Ok(vec)
+}
+
 fn find_last_commit(repo: &Repository) -> Result<Commit, git2::Error> {
     let obj = repo.head()?.resolve()?.peel(ObjectType::Commit)?;
     obj.into_commit()
         .map_err(|_| git2::Error::from_str("Couldn't find commit"))
 }
 
-fn get_file_content_from_last_commit(
+fn get_file_content_from_commit(
     repo: &Repository,
-    file_name: &String,
+    commit: &Commit,
+    file_path: &str,
 ) -> Result<Vec<u8>, git2::Error> {
-    let last_commit = find_last_commit(&repo)?;
-    let tree = last_commit.tree()?;
-    match tree.get_name(&file_name) {
-        Some(file) => {
-            if file.kind().unwrap() == ObjectType::Blob {
-                let obj = file.to_object(repo)?;
-                let blob = obj.as_blob().unwrap();
-                return Ok(blob.content().to_vec());
-            }
-        }
-        None => {
-            return Err(git2::Error::from_str(
-                format!("Error while retrieving the file with name {}", &file_name).as_str(),
-            ))
-        }
+    let entry = commit.tree()?.get_path(Path::new(file_path))?;
+    match entry.to_object(repo)?.into_blob() {
+        Ok(blob) => Ok(blob.content().to_vec()),
+        Err(_) => Err(git2::Error::new(
+            ErrorCode::NotFound,
+            git2::ErrorClass::Tree,
+            format!("The path {} is not a file", file_path),
+        )),
     }
-    Ok(vec![0])
 }
 
 fn create_commit(repository: &Repository, commit_message: &str) -> Result<git2::Oid, git2::Error> {
@@ -0,0 +1,306 @@
+use crate::configuration::Settings;
+use crate::extractors::WorkspaceName;
+use actix_multipart::form::tempfile::TempFile;
+use actix_multipart::form::text::Text;
+use actix_multipart::form::MultipartForm;
+use actix_web::{web, HttpResponse};
+use git2::{IndexAddOption, Oid, Repository, Signature};
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(MultipartForm)]
+pub struct Import {
+    pub bundle: Option<TempFile>,
+    pub archive: Option<TempFile>,
+    pub path: Option<Text<String>>,
+    pub commit_message: Option<Text<String>>,
+}
+
+#[derive(Debug)]
+enum ImportError {
+    InvalidSource(String),
+    PathNotAllowed(String),
+    Git(git2::Error),
+    Io(std::io::Error),
+    Zip(zip::result::ZipError),
+}
+
+impl std::fmt::Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportError::InvalidSource(message) => write!(f, "{}", message),
+            ImportError::PathNotAllowed(message) => write!(f, "{}", message),
+            ImportError::Git(e) => write!(f, "{}", e),
+            ImportError::Io(e) => write!(f, "{}", e),
+            ImportError::Zip(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<git2::Error> for ImportError {
+    fn from(e: git2::Error) -> Self {
+        ImportError::Git(e)
+    }
+}
+
+impl From<std::io::Error> for ImportError {
+    fn from(e: std::io::Error) -> Self {
+        ImportError::Io(e)
+    }
+}
+
+impl From<zip::result::ZipError> for ImportError {
+    fn from(e: zip::result::ZipError) -> Self {
+        ImportError::Zip(e)
+    }
+}
+
+// curl -X POST -H 'Content-Type: multipart/form-data' -F bundle=@/path/to/workspace.bundle -v http://127.0.0.1:8000/workspaces/{workspace_name}/import
+// curl -X POST -H 'Content-Type: multipart/form-data' -F archive=@/path/to/documents.zip -Fcommit_message='commit message' -v http://127.0.0.1:8000/workspaces/{workspace_name}/import
+// curl -X POST -H 'Content-Type: multipart/form-data' -Fpath='/path/to/repository' -v http://127.0.0.1:8000/workspaces/{workspace_name}/import
+pub async fn import_workspace(
+    workspace_name: WorkspaceName,
+    form: MultipartForm<Import>,
+    workspace_path: web::Data<String>,
+    settings: web::Data<Settings>,
+) -> HttpResponse {
+    let workspace_name = workspace_name.into_inner();
+    let workspace_directory = PathBuf::from(&workspace_path.as_str());
+    let workspace = get_workspace(&workspace_name, &workspace_directory);
+
+    if workspace.exists() {
+        return HttpResponse::Conflict().finish();
+    }
+
+    let result = match (&form.bundle, &form.archive, &form.path) {
+        (Some(bundle), None, None) => import_bundle(bundle.file.path(), &workspace),
+        (None, Some(archive), None) => {
+            let commit_message = form
+                .commit_message
+                .as_ref()
+                .map(|m| m.as_str())
+                .unwrap_or("Import documents");
+            import_archive(archive.file.path(), &workspace, commit_message)
+        }
+        (None, None, Some(path)) => {
+            import_repository(path.as_str(), &workspace, &settings.import_allowed_paths)
+        }
+        _ => {
+            return HttpResponse::BadRequest()
+                .body("Provide exactly one of the fields bundle, archive or path.")
+        }
+    };
+
+    match result {
+        Ok(()) => HttpResponse::Ok().finish(),
+        Err(e) => {
+            eprintln!("Error while importing the workspace: {}", e);
+            if workspace.exists() {
+                _ = fs::remove_dir_all(workspace.as_path());
+            }
+            match e {
+                ImportError::InvalidSource(_) | ImportError::Zip(_) => {
+                    HttpResponse::BadRequest().body(e.to_string())
+                }
+                ImportError::PathNotAllowed(_) => HttpResponse::Forbidden().body(e.to_string()),
+                ImportError::Git(_) | ImportError::Io(_) => {
+                    HttpResponse::InternalServerError().finish()
+                }
+            }
+        }
+    }
+}
+
+fn get_workspace(workspace_name: &str, workspace_directory: &Path) -> PathBuf {
+    let mut workspace = PathBuf::from(workspace_directory);
+    workspace.push(workspace_name);
+    workspace
+}
+
+// Git bundle v2 and v3 files: a header line, optional "@" capability lines (v3 only), one
+// "<oid> <refname>" line per reference, an empty line and the packfile.
+fn import_bundle(bundle_path: &Path, workspace: &Path) -> Result<(), ImportError> {
+    let mut reader = BufReader::new(fs::File::open(bundle_path)?);
+    let mut header = String::new();
+    reader.read_line(&mut header)?;
+    if header != "# v2 git bundle\n" && header != "# v3 git bundle\n" {
+        return Err(ImportError::InvalidSource(
+            "The uploaded file is not a git bundle.".to_string(),
+        ));
+    }
+    let mut references: Vec<(Oid, String)> = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(ImportError::InvalidSource(
+                "The git bundle does not contain a packfile.".to_string(),
+            ));
+        }
+        let line = line.trim_end_matches('\n');
+        if line.is_empty() {
+            break;
+        }
+        if line.starts_with('@') {
+            continue;
+        }
+        if line.starts_with('-') {
+            return Err(ImportError::InvalidSource(
+                "The git bundle does not contain the full history.".to_string(),
+            ));
+        }
+        match line.split_once(' ') {
+            Some((oid, name)) if Oid::from_str(oid).is_ok() => {
+                references.push((Oid::from_str(oid)?, name.to_string()))
+            }
+            _ => {
+                return Err(ImportError::InvalidSource(format!(
+                    "Invalid reference line in the git bundle: {}",
+                    line
+                )))
+            }
+        }
+    }
+
+    let repository = Repository::init(workspace)?;
+    let odb = repository.odb()?;
+    let mut pack_writer = odb.packwriter()?;
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        pack_writer.write_all(&buffer[..read])?;
+    }
+    pack_writer.commit()?;
+
+    let mut head_target: Option<Oid> = None;
+    for (oid, name) in &references {
+        if name == "HEAD" {
+            head_target = Some(*oid);
+        } else if name.starts_with("refs/heads/") || name.starts_with("refs/tags/") {
+            repository.reference(name, *oid, true, "import from git bundle")?;
+        }
+    }
+    let head_branch = references
+        .iter()
+        .filter(|(oid, name)| {
+            name.starts_with("refs/heads/") && head_target.unwrap_or(*oid) == *oid
+        })
+        .map(|(_, name)| name.clone())
+        .min_by_key(|name| name != "refs/heads/master");
+    match head_branch {
+        Some(name) => finish_import(&repository, &name),
+        None => Err(ImportError::InvalidSource(
+            "The git bundle does not contain any branch.".to_string(),
+        )),
+    }
+}
+
+fn import_archive(
+    archive_path: &Path,
+    workspace: &Path,
+    commit_message: &str,
+) -> Result<(), ImportError> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(archive_path)?)?;
+    let repository = Repository::init(workspace)?;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let file_path = match file.enclosed_name() {
+            Some(p) => p.to_owned(),
+            None => {
+                return Err(ImportError::InvalidSource(format!(
+                    "Invalid file path in the archive: {}",
+                    file.name()
+                )))
+            }
+        };
+        if file.is_dir()
+            || file_path
+                .components()
+                .any(|c| c == Component::Normal(".git".as_ref()))
+        {
+            continue;
+        }
+        let destination_path = workspace.join(&file_path);
+        if let Some(parent) = destination_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut destination_file = fs::File::create(&destination_path)?;
+        std::io::copy(&mut file, &mut destination_file)?;
+    }
+
+    let mut index = repository.index()?;
+    index.add_all(["*"].iter(), IndexAddOption::DEFAULT, None)?;
+    index.write()?;
+    let oid = index.write_tree()?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    let tree = repository.find_tree(oid)?;
+    repository.commit(
+        Some("refs/heads/master"),
+        &signature,
+        &signature,
+        commit_message,
+        &tree,
+        &[],
+    )?;
+    finish_import(&repository, "refs/heads/master")
+}
+
+fn import_repository(
+    path: &str,
+    workspace: &Path,
+    allowed_paths: &[String],
+) -> Result<(), ImportError> {
+    let source_path = fs::canonicalize(path)
+        .map_err(|_| ImportError::InvalidSource(format!("The path {} does not exist.", path)))?;
+    let allowed = allowed_paths
+        .iter()
+        .filter_map(|p| fs::canonicalize(p).ok())
+        .any(|p| source_path.starts_with(p));
+    if !allowed {
+        return Err(ImportError::PathNotAllowed(format!(
+            "The path {} is not in the list of allowed import paths.",
+            path
+        )));
+    }
+    let source_repository = Repository::open(&source_path).map_err(|_| {
+        ImportError::InvalidSource(format!("The path {} is not a git repository.", path))
+    })?;
+
+    let repository = Repository::init(workspace)?;
+    repository
+        .remote_anonymous(source_repository.path().to_str().unwrap())?
+        .fetch(
+            &["+refs/heads/*:refs/heads/*", "+refs/tags/*:refs/tags/*"],
+            None,
+            None,
+        )?;
+    let head_branch = match source_repository.head() {
+        Ok(head) if head.is_branch() => head.name().unwrap_or("refs/heads/master").to_string(),
+        _ => "refs/heads/master".to_string(),
+    };
+    finish_import(&repository, &head_branch)
+}
+
+fn finish_import(repository: &Repository, reference_name: &str) -> Result<(), ImportError> {
+    repository.set_head(reference_name)?;
+    repository.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))?;
+    let created = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    let mut config = repository.config()?;
+    // The branch checked out by the import becomes the default branch of the workspace.
+    config.set_str(
+        "docversions.defaultbranch",
+        reference_name
+            .strip_prefix("refs/heads/")
+            .unwrap_or(reference_name),
+    )?;
+    config.set_i64("docversions.created", created)?;
+    config.set_i64("docversions.updated", created)?;
+    Ok(())
+}
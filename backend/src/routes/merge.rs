@@ -0,0 +1,240 @@
+use crate::extractors::{BranchName, WorkspaceName};
+use crate::routes::{check_rebase_in_progress, create_merge_session};
+use actix_web::{web, HttpResponse};
+use git2::{BranchType, Commit, Index, Oid, Repository, Signature};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct MergeBranch {
+    pub source: String,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum MergeStatus {
+    UpToDate,
+    FastForward,
+    MergeCommit,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct MergeResult {
+    status: MergeStatus,
+    commit_uuid: String,
+}
+
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    BothModified,
+    BothAdded,
+    DeletedByUs,
+    DeletedByThem,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct MergeConflict {
+    pub path: String,
+    pub kind: ConflictKind,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct MergeConflicts {
+    session_id: String,
+    source: String,
+    target: String,
+    conflicts: Vec<MergeConflict>,
+}
+
+// curl -X POST -H 'Content-Type: application/json' -d '{"source":"source_branch_name","message":"merge message"}' -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}/merge
+pub async fn merge_branch(
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
+    merge: web::Json<MergeBranch>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let workspace = PathBuf::from(workspace_path.as_str()).join(workspace_name.as_str());
+    let target_branch = branch_name.into_inner();
+    let source_branch = match BranchName::parse(&merge.source) {
+        Ok(b) => b.into_inner(),
+        Err(reason) => return HttpResponse::BadRequest().body(reason),
+    };
+    let repository = match Repository::open(&workspace) {
+        Ok(r) => r,
+        Err(e) => {
+            println!("Error while retrieving the repository: {:#?}", e);
+            return HttpResponse::NotFound().finish();
+        }
+    };
+    if let Some(response) = check_rebase_in_progress(&repository) {
+        return response;
+    }
+    let target = match find_branch_commit(&repository, &target_branch) {
+        Some(c) => c,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    let source = match find_branch_commit(&repository, &source_branch) {
+        Some(c) => c,
+        None => return HttpResponse::NotFound().finish(),
+    };
+
+    let merge_base = match repository.merge_base(target.id(), source.id()) {
+        Ok(b) => b,
+        Err(e) => {
+            eprintln!(
+                "Error while finding the merge base of {} and {}: {:#?}",
+                &target_branch, &source_branch, e
+            );
+            return HttpResponse::Conflict().body(format!(
+                "The branches {} and {} do not have a common history.",
+                &target_branch, &source_branch
+            ));
+        }
+    };
+    if merge_base == source.id() {
+        return HttpResponse::Ok().json(MergeResult {
+            status: MergeStatus::UpToDate,
+            commit_uuid: target.id().to_string(),
+        });
+    }
+    if merge_base == target.id() {
+        return match update_branch(&repository, &target_branch, source.id(), "fast-forward") {
+            Ok(()) => HttpResponse::Ok().json(MergeResult {
+                status: MergeStatus::FastForward,
+                commit_uuid: source.id().to_string(),
+            }),
+            Err(e) => {
+                eprintln!("Error while fast-forwarding {}: {:#?}", &target_branch, e);
+                HttpResponse::InternalServerError().finish()
+            }
+        };
+    }
+
+    let mut index = match repository.merge_commits(&target, &source, None) {
+        Ok(i) => i,
+        Err(e) => {
+            eprintln!("Error while merging {}: {:#?}", &source_branch, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    let message = merge
+        .message
+        .clone()
+        .unwrap_or_else(|| format!("Merge branch '{}' into {}", source_branch, target_branch));
+    if index.has_conflicts() {
+        // The conflicts are kept in a merge session, so they can be resolved file by file.
+        return match create_merge_session(
+            &repository,
+            &source_branch,
+            &target_branch,
+            source.id(),
+            target.id(),
+            &message,
+            &index,
+        ) {
+            Ok(session) => HttpResponse::Conflict().json(MergeConflicts {
+                session_id: session.id,
+                source: source_branch,
+                target: target_branch,
+                conflicts: session
+                    .files
+                    .into_iter()
+                    .map(|f| MergeConflict {
+                        path: f.path,
+                        kind: f.kind,
+                    })
+                    .collect(),
+            }),
+            Err(e) => {
+                eprintln!("Error while storing the merge conflicts: {:#?}", e);
+                HttpResponse::InternalServerError().finish()
+            }
+        };
+    }
+    match create_merge_commit(&repository, &mut index, &target, &source, &message)
+        .and_then(|oid| update_branch(&repository, &target_branch, oid, "merge").map(|_| oid))
+    {
+        Ok(oid) => HttpResponse::Ok().json(MergeResult {
+            status: MergeStatus::MergeCommit,
+            commit_uuid: oid.to_string(),
+        }),
+        Err(e) => {
+            eprintln!("Error while creating the merge commit: {:#?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+fn find_branch_commit<'a>(repository: &'a Repository, branch_name: &str) -> Option<Commit<'a>> {
+    repository
+        .find_branch(branch_name, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .ok()
+}
+
+pub fn get_conflicts(index: &Index) -> Result<Vec<MergeConflict>, git2::Error> {
+    let mut conflicts = Vec::new();
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        let entry = match conflict
+            .our
+            .as_ref()
+            .or(conflict.their.as_ref())
+            .or(conflict.ancestor.as_ref())
+        {
+            Some(e) => e,
+            None => continue,
+        };
+        let kind = match (&conflict.ancestor, &conflict.our, &conflict.their) {
+            (_, None, _) => ConflictKind::DeletedByUs,
+            (_, _, None) => ConflictKind::DeletedByThem,
+            (None, _, _) => ConflictKind::BothAdded,
+            _ => ConflictKind::BothModified,
+        };
+        conflicts.push(MergeConflict {
+            path: String::from_utf8_lossy(&entry.path).to_string(),
+            kind,
+        });
+    }
+    Ok(conflicts)
+}
+
+pub fn create_merge_commit(
+    repository: &Repository,
+    index: &mut Index,
+    target: &Commit,
+    source: &Commit,
+    message: &str,
+) -> Result<Oid, git2::Error> {
+    let tree = repository.find_tree(index.write_tree_to(repository)?)?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    repository.commit(
+        None,
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &[target, source],
+    )
+}
+
+// Moves the branch to the new commit and refreshes the working directory when the branch is
+// checked out.
+pub fn update_branch(
+    repository: &Repository,
+    branch_name: &str,
+    oid: Oid,
+    log_message: &str,
+) -> Result<(), git2::Error> {
+    let reference_name = format!("refs/heads/{}", branch_name);
+    repository.reference(&reference_name, oid, true, log_message)?;
+    let is_head = repository
+        .head()
+        .map(|h| h.name() == Some(reference_name.as_str()))
+        .unwrap_or(false);
+    if is_head {
+        repository.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))?;
+    }
+    Ok(())
+}
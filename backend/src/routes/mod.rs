@@ -1,9 +1,47 @@
+mod backups;
 mod branches;
+mod cherry_pick;
+mod conflicts;
+mod export;
 mod files;
 mod health_check;
+mod import;
+mod merge;
+mod protection;
+mod quotas;
+mod rebase;
+mod revert;
+mod squash;
+mod tags;
+mod trash;
 mod workspaces;
 
+pub use backups::*;
 pub use branches::*;
+pub use cherry_pick::*;
+pub use conflicts::*;
+pub use export::*;
 pub use files::*;
 pub use health_check::*;
+pub use import::*;
+pub use merge::*;
+pub use protection::*;
+pub use quotas::*;
+pub use rebase::*;
+pub use revert::*;
+pub use squash::*;
+pub use tags::*;
+pub use trash::*;
 pub use workspaces::*;
+
+// Reads resolve the commit of a branch, or of a tag when there is no branch with that name, from
+// the object database, so HEAD and the working tree are left untouched by a read on another ref.
+pub fn find_branch_or_tag_commit<'a>(
+    repository: &'a git2::Repository,
+    name: &str,
+) -> Result<git2::Commit<'a>, git2::Error> {
+    repository
+        .find_reference(&format!("refs/heads/{}", name))
+        .or_else(|_| repository.find_reference(&format!("refs/tags/{}", name)))?
+        .peel_to_commit()
+}
@@ -0,0 +1,132 @@
+use crate::extractors::{BranchName, WorkspaceName};
+use actix_web::{web, HttpResponse};
+use git2::{BranchType, ErrorCode, Repository};
+use std::path::PathBuf;
+
+// The rules are stored in the repository configuration, in the section of the branch, so they
+// follow the branch when it is renamed.
+#[derive(Debug, Clone, Copy, Default, serde::Deserialize, serde::Serialize)]
+pub struct BranchProtection {
+    #[serde(default)]
+    pub no_direct_commits: bool,
+    #[serde(default)]
+    pub no_delete_or_reset: bool,
+}
+
+// curl -X GET -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}/protection
+pub async fn retrieve_branch_protection(
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let workspace = PathBuf::from(workspace_path.as_str()).join(workspace_name.as_str());
+    let repository = match Repository::open(&workspace) {
+        Ok(r) => r,
+        Err(e) => {
+            println!("Error while retrieving the repository: {:#?}", e);
+            return HttpResponse::NotFound().finish();
+        }
+    };
+    if repository
+        .find_branch(&branch_name, BranchType::Local)
+        .is_err()
+    {
+        return HttpResponse::NotFound().finish();
+    }
+    HttpResponse::Ok().json(get_branch_protection(&repository, &branch_name))
+}
+
+// curl -X PUT -H 'Content-Type: application/json' -d '{"no_direct_commits":true,"no_delete_or_reset":true}' -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}/protection
+pub async fn update_branch_protection(
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
+    protection: web::Json<BranchProtection>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let workspace = PathBuf::from(workspace_path.as_str()).join(workspace_name.as_str());
+    let repository = match Repository::open(&workspace) {
+        Ok(r) => r,
+        Err(e) => {
+            println!("Error while retrieving the repository: {:#?}", e);
+            return HttpResponse::NotFound().finish();
+        }
+    };
+    if repository
+        .find_branch(&branch_name, BranchType::Local)
+        .is_err()
+    {
+        return HttpResponse::NotFound().finish();
+    }
+    match set_branch_protection(&repository, &branch_name, &protection) {
+        Ok(()) => HttpResponse::Ok().json(protection.into_inner()),
+        Err(e) => {
+            eprintln!(
+                "Error while updating the protection of the branch {}: {:#?}",
+                branch_name.as_str(),
+                e
+            );
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+pub fn get_branch_protection(repository: &Repository, branch_name: &str) -> BranchProtection {
+    let config = match repository.config() {
+        Ok(c) => c,
+        Err(_) => return BranchProtection::default(),
+    };
+    let get_rule = |rule: &str| {
+        config
+            .get_bool(&format!("branch.{}.{}", branch_name, rule))
+            .unwrap_or(false)
+    };
+    BranchProtection {
+        no_direct_commits: get_rule("nodirectcommits"),
+        no_delete_or_reset: get_rule("nodeleteorreset"),
+    }
+}
+
+fn set_branch_protection(
+    repository: &Repository,
+    branch_name: &str,
+    protection: &BranchProtection,
+) -> Result<(), git2::Error> {
+    let mut config = repository.config()?;
+    for (rule, enabled) in [
+        ("nodirectcommits", protection.no_direct_commits),
+        ("nodeleteorreset", protection.no_delete_or_reset),
+    ] {
+        let key = format!("branch.{}.{}", branch_name, rule);
+        if enabled {
+            config.set_bool(&key, true)?;
+        } else if let Err(e) = config.remove(&key) {
+            if e.code() != ErrorCode::NotFound {
+                return Err(e);
+            }
+        }
+    }
+    Ok(())
+}
+
+// Returns the response to send when the branch only accepts merges.
+pub fn check_direct_commits(repository: &Repository, branch_name: &str) -> Option<HttpResponse> {
+    if get_branch_protection(repository, branch_name).no_direct_commits {
+        return Some(HttpResponse::Forbidden().body(format!(
+            "The branch {} is protected and only accepts changes through merges.",
+            branch_name
+        )));
+    }
+    None
+}
+
+// Returns the response to send when the branch cannot be deleted, renamed or moved to an older
+// commit.
+pub fn check_delete_or_reset(repository: &Repository, branch_name: &str) -> Option<HttpResponse> {
+    if get_branch_protection(repository, branch_name).no_delete_or_reset {
+        return Some(HttpResponse::Forbidden().body(format!(
+            "The branch {} is protected and cannot be deleted, renamed or reset.",
+            branch_name
+        )));
+    }
+    None
+}
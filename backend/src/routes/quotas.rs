@@ -0,0 +1,172 @@
+use crate::configuration::{QuotaLimits, QuotaSettings, Settings};
+use crate::extractors::WorkspaceName;
+use actix_multipart::form::MultipartFormConfig;
+use actix_multipart::MultipartError;
+use actix_web::error::{InternalError, PayloadError};
+use actix_web::{web, HttpResponse};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WorkspaceUsage {
+    pub repository_size: u64,
+    pub files: u64,
+    pub limits: QuotaLimits,
+}
+
+#[derive(Debug)]
+pub enum QuotaExceeded {
+    FileSize(u64),
+    RepositorySize(u64),
+    Files(u64),
+}
+
+impl fmt::Display for QuotaExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuotaExceeded::FileSize(max) => {
+                write!(
+                    f,
+                    "The file exceeds the maximum file size of {} bytes.",
+                    max
+                )
+            }
+            QuotaExceeded::RepositorySize(max) => write!(
+                f,
+                "The workspace exceeds the maximum repository size of {} bytes.",
+                max
+            ),
+            QuotaExceeded::Files(max) => write!(
+                f,
+                "The workspace exceeds the maximum number of {} files.",
+                max
+            ),
+        }
+    }
+}
+
+impl QuotaExceeded {
+    // A single file larger than the limit is rejected as too large, while a workspace that ran out
+    // of space or files is reported as out of storage.
+    pub fn to_response(&self) -> HttpResponse {
+        match self {
+            QuotaExceeded::FileSize(_) => HttpResponse::PayloadTooLarge().body(self.to_string()),
+            QuotaExceeded::RepositorySize(_) | QuotaExceeded::Files(_) => {
+                HttpResponse::InsufficientStorage().body(self.to_string())
+            }
+        }
+    }
+}
+
+// The text fields sent next to an uploaded file, such as the commit message, are kept in memory.
+const MAX_TEXT_FIELDS_SIZE: usize = 64 * 1024;
+
+// Uploads are cut off while they are streamed once they exceed the largest file size allowed to
+// any workspace, check_upload_quota then enforces the exact limit of the targeted workspace.
+pub fn get_upload_form_config(quotas: &QuotaSettings) -> MultipartFormConfig {
+    let config = MultipartFormConfig::default();
+    let max_file_size = match quotas.max_file_size {
+        Some(max_file_size) => quotas
+            .workspace_overrides
+            .iter()
+            .filter_map(|workspace_override| workspace_override.max_file_size)
+            .fold(max_file_size, u64::max),
+        None => return config,
+    };
+    let quotas = quotas.clone();
+    config
+        .total_limit(
+            usize::try_from(max_file_size)
+                .unwrap_or(usize::MAX)
+                .saturating_add(MAX_TEXT_FIELDS_SIZE),
+        )
+        .memory_limit(MAX_TEXT_FIELDS_SIZE)
+        .error_handler(move |e, req| match e {
+            MultipartError::Payload(PayloadError::Overflow) => {
+                let max_file_size = req
+                    .match_info()
+                    .get("workspace_name")
+                    .and_then(|workspace_name| quotas.get_limits(workspace_name).max_file_size)
+                    .unwrap_or(max_file_size);
+                InternalError::from_response(
+                    e,
+                    QuotaExceeded::FileSize(max_file_size).to_response(),
+                )
+                .into()
+            }
+            e => e.into(),
+        })
+}
+
+// curl -X GET -v http://127.0.0.1:8000/workspaces/{workspace_name}/usage
+pub async fn retrieve_workspace_usage(
+    workspace_name: WorkspaceName,
+    workspace_path: web::Data<String>,
+    settings: web::Data<Settings>,
+) -> HttpResponse {
+    let workspace = PathBuf::from(workspace_path.as_str()).join(workspace_name.as_str());
+    if !workspace.is_dir() {
+        return HttpResponse::NotFound().finish();
+    }
+    HttpResponse::Ok().json(get_workspace_usage(
+        &workspace,
+        settings.quotas.get_limits(&workspace_name),
+    ))
+}
+
+pub fn get_workspace_usage(workspace: &Path, limits: QuotaLimits) -> WorkspaceUsage {
+    let mut usage = WorkspaceUsage {
+        repository_size: 0,
+        files: 0,
+        limits,
+    };
+    for entry in WalkDir::new(workspace).into_iter().filter_map(Result::ok) {
+        let metadata = match entry.metadata() {
+            Ok(m) if m.is_file() => m,
+            _ => continue,
+        };
+        usage.repository_size += metadata.len();
+        if !entry
+            .path()
+            .strip_prefix(workspace)
+            .map(|p| p.starts_with(".git"))
+            .unwrap_or(true)
+        {
+            usage.files += 1;
+        }
+    }
+    usage
+}
+
+// Checks that writing a file of "file_size" bytes over a file of "replaced_size" bytes keeps the
+// workspace within its limits. "replaced_size" is None when the file is new.
+pub fn check_upload_quota(
+    workspace: &Path,
+    limits: QuotaLimits,
+    file_size: u64,
+    replaced_size: Option<u64>,
+) -> Result<(), QuotaExceeded> {
+    if let Some(max) = limits.max_file_size {
+        if file_size > max {
+            return Err(QuotaExceeded::FileSize(max));
+        }
+    }
+    if limits.max_repository_size.is_none() && limits.max_files.is_none() {
+        return Ok(());
+    }
+    let usage = get_workspace_usage(workspace, limits);
+    if let Some(max) = limits.max_repository_size {
+        let repository_size =
+            (usage.repository_size + file_size).saturating_sub(replaced_size.unwrap_or_default());
+        if repository_size > max {
+            return Err(QuotaExceeded::RepositorySize(max));
+        }
+    }
+    if let Some(max) = limits.max_files {
+        if replaced_size.is_none() && usage.files + 1 > max {
+            return Err(QuotaExceeded::Files(max));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,488 @@
+use crate::extractors::{BranchName, FilePath, WorkspaceName};
+use crate::routes::{
+    check_delete_or_reset, check_direct_commits, get_conflicts, get_default_branch, MergeConflict,
+    ResolveFile,
+};
+use actix_multipart::form::MultipartForm;
+use actix_web::{web, HttpResponse};
+use git2::{BranchType, ErrorCode, Oid, Rebase, Repository, RepositoryState, Signature};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct RebaseBranch {
+    pub onto: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RebaseStatus {
+    UpToDate,
+    Completed,
+    Aborted,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct RebaseResult {
+    status: RebaseStatus,
+    branch: String,
+    commit_uuid: String,
+}
+
+// The state of a rebase stopped on conflicts: "commit_uuid" is the commit being replayed, and
+// "operation" its position among the "operations" commits to replay.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct RebaseState {
+    branch: Option<String>,
+    original_commit_uuid: Option<String>,
+    commit_uuid: Option<String>,
+    operation: Option<usize>,
+    operations: usize,
+    conflicts: Vec<MergeConflict>,
+}
+
+enum RebaseOutcome {
+    Completed(String),
+    Stopped(RebaseState),
+}
+
+// The rebase runs in the working directory of the workspace, so that it can stop on conflicts and
+// be continued or aborted later, also after a restart.
+// curl -X POST -H 'Content-Type: application/json' -d '{"onto":"master"}' -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}/rebase
+pub async fn rebase_branch(
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
+    rebase_branch: Option<web::Json<RebaseBranch>>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let repository = match get_repository(&workspace_path, &workspace_name) {
+        Some(r) => r,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    let branch_name = branch_name.into_inner();
+    let onto_name = match rebase_branch.and_then(|r| r.into_inner().onto) {
+        Some(onto) => match BranchName::parse(&onto) {
+            Ok(b) => b.into_inner(),
+            Err(reason) => return HttpResponse::BadRequest().body(reason),
+        },
+        None => get_default_branch(&repository),
+    };
+    if onto_name == branch_name {
+        return HttpResponse::BadRequest().body(format!(
+            "The branch {} cannot be rebased onto itself.",
+            &branch_name
+        ));
+    }
+    let branch = match repository.find_branch(&branch_name, BranchType::Local) {
+        Ok(b) => b,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    let onto = match repository
+        .find_branch(&onto_name, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+    {
+        Ok(c) => c,
+        Err(_) => {
+            return HttpResponse::NotFound()
+                .body(format!("The branch {} does not exist.", &onto_name))
+        }
+    };
+    if let Some(response) = check_rebase_in_progress(&repository) {
+        return response;
+    }
+    // Rebasing rewrites the history with new commits, so both protection rules refuse it.
+    if let Some(response) = check_delete_or_reset(&repository, &branch_name) {
+        return response;
+    }
+    if let Some(response) = check_direct_commits(&repository, &branch_name) {
+        return response;
+    }
+    let tip = match branch.get().peel_to_commit() {
+        Ok(c) => c.id(),
+        Err(e) => {
+            eprintln!(
+                "Error while retrieving the branch {}: {:#?}",
+                &branch_name, e
+            );
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    if tip == onto.id()
+        || repository
+            .graph_descendant_of(tip, onto.id())
+            .unwrap_or(false)
+    {
+        return HttpResponse::Ok().json(RebaseResult {
+            status: RebaseStatus::UpToDate,
+            branch: branch_name,
+            commit_uuid: tip.to_string(),
+        });
+    }
+
+    let rebase = checkout_branch(&repository, &branch_name)
+        .and_then(|_| repository.reference_to_annotated_commit(branch.get()))
+        .and_then(|branch_commit| {
+            let onto_commit = repository.find_annotated_commit(onto.id())?;
+            repository.rebase(Some(&branch_commit), Some(&onto_commit), None, None)
+        });
+    let mut rebase = match rebase {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!(
+                "Error while starting the rebase of {}: {:#?}",
+                &branch_name, e
+            );
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    to_response(&repository, &mut rebase)
+}
+
+// curl -X GET -v http://127.0.0.1:8000/workspaces/{workspace_name}/rebase
+pub async fn retrieve_rebase(
+    workspace_name: WorkspaceName,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let repository = match get_repository(&workspace_path, &workspace_name) {
+        Some(r) => r,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    let mut rebase = match repository.open_rebase(None) {
+        Ok(r) => r,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    match get_rebase_state(&repository, &mut rebase) {
+        Ok(state) => HttpResponse::Ok().json(state),
+        Err(e) => {
+            eprintln!("Error while retrieving the rebase: {:#?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+// curl -X POST -v http://127.0.0.1:8000/workspaces/{workspace_name}/rebase/continue
+pub async fn continue_rebase(
+    workspace_name: WorkspaceName,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let repository = match get_repository(&workspace_path, &workspace_name) {
+        Some(r) => r,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    let mut rebase = match repository.open_rebase(None) {
+        Ok(r) => r,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    let has_conflicts = match repository.index() {
+        Ok(index) => index.has_conflicts(),
+        Err(e) => {
+            eprintln!("Error while retrieving the index: {:#?}", e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    if has_conflicts {
+        return match get_rebase_state(&repository, &mut rebase) {
+            Ok(state) => HttpResponse::Conflict().json(state),
+            Err(e) => {
+                eprintln!("Error while retrieving the rebase: {:#?}", e);
+                HttpResponse::InternalServerError().finish()
+            }
+        };
+    }
+    match is_rebase_head(&repository) {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::Conflict()
+                .body("The HEAD of the workspace has moved since the rebase stopped.".to_string())
+        }
+        Err(e) => {
+            eprintln!("Error while retrieving the rebase: {:#?}", e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+    if rebase.operation_current().is_some() {
+        if let Err(e) = commit_operation(&mut rebase) {
+            eprintln!("Error while continuing the rebase: {:#?}", e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+    to_response(&repository, &mut rebase)
+}
+
+// curl -X POST -v http://127.0.0.1:8000/workspaces/{workspace_name}/rebase/abort
+pub async fn abort_rebase(
+    workspace_name: WorkspaceName,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let repository = match get_repository(&workspace_path, &workspace_name) {
+        Some(r) => r,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    let mut rebase = match repository.open_rebase(None) {
+        Ok(r) => r,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    let branch = get_branch_name(&rebase).unwrap_or_default();
+    let commit_uuid = rebase
+        .orig_head_id()
+        .map(|oid| oid.to_string())
+        .unwrap_or_default();
+    match rebase.abort() {
+        Ok(()) => HttpResponse::Ok().json(RebaseResult {
+            status: RebaseStatus::Aborted,
+            branch,
+            commit_uuid,
+        }),
+        Err(e) => {
+            eprintln!("Error while aborting the rebase: {:#?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+// Files inside directories are addressed with the "path" query parameter, and only the files
+// changed by the commit being replayed can be resolved.
+// curl -X PUT -H 'Content-Type: multipart/form-data' -F file=@/path/to/file -v http://127.0.0.1:8000/workspaces/{workspace_name}/rebase/files/{file_name}
+// curl -X PUT -H 'Content-Type: multipart/form-data' -F file=@/path/to/file -v http://127.0.0.1:8000/workspaces/{workspace_name}/rebase/files?path={file_path}
+pub async fn resolve_rebase_file(
+    workspace_name: WorkspaceName,
+    file_path: FilePath,
+    form: MultipartForm<ResolveFile>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let repository = match get_repository(&workspace_path, &workspace_name) {
+        Some(r) => r,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    let mut rebase = match repository.open_rebase(None) {
+        Ok(r) => r,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    match is_rebase_file(&repository, &mut rebase, &file_path) {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            eprintln!("Error while retrieving the rebase: {:#?}", e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+    let path = PathBuf::from(workspace_path.as_str())
+        .join(workspace_name.as_str())
+        .join(file_path.as_str());
+    let resolve = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::copy(form.file.file.path(), &path))
+        .map_err(|e| git2::Error::from_str(&e.to_string()))
+        .and_then(|_| repository.index())
+        .and_then(|mut index| {
+            index.add_path(Path::new(file_path.as_str()))?;
+            index.write()
+        });
+    if let Err(e) = resolve {
+        eprintln!(
+            "Error while resolving the file {}: {:#?}",
+            file_path.as_str(),
+            e
+        );
+        return HttpResponse::InternalServerError().finish();
+    }
+    match get_rebase_state(&repository, &mut rebase) {
+        Ok(state) => HttpResponse::Ok().json(state),
+        Err(e) => {
+            eprintln!("Error while retrieving the rebase: {:#?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+// curl -X DELETE -v http://127.0.0.1:8000/workspaces/{workspace_name}/rebase/files/{file_name}
+// curl -X DELETE -v http://127.0.0.1:8000/workspaces/{workspace_name}/rebase/files?path={file_path}
+pub async fn resolve_rebase_file_as_deleted(
+    workspace_name: WorkspaceName,
+    file_path: FilePath,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let repository = match get_repository(&workspace_path, &workspace_name) {
+        Some(r) => r,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    let mut rebase = match repository.open_rebase(None) {
+        Ok(r) => r,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    match is_rebase_file(&repository, &mut rebase, &file_path) {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            eprintln!("Error while retrieving the rebase: {:#?}", e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+    let path = PathBuf::from(workspace_path.as_str())
+        .join(workspace_name.as_str())
+        .join(file_path.as_str());
+    if path.is_file() {
+        if let Err(e) = fs::remove_file(&path) {
+            eprintln!(
+                "Error while deleting the file {}: {:#?}",
+                file_path.as_str(),
+                e
+            );
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+    let resolve = repository.index().and_then(|mut index| {
+        index.remove_path(Path::new(file_path.as_str()))?;
+        index.write()
+    });
+    if let Err(e) = resolve {
+        eprintln!(
+            "Error while resolving the file {}: {:#?}",
+            file_path.as_str(),
+            e
+        );
+        return HttpResponse::InternalServerError().finish();
+    }
+    match get_rebase_state(&repository, &mut rebase) {
+        Ok(state) => HttpResponse::Ok().json(state),
+        Err(e) => {
+            eprintln!("Error while retrieving the rebase: {:#?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+// A rebase stopped on conflicts owns the working directory and HEAD of the workspace until it is
+// continued or aborted, so the routes that check out a branch or move one return 409 meanwhile.
+pub fn check_rebase_in_progress(repository: &Repository) -> Option<HttpResponse> {
+    if repository.state() != RepositoryState::Clean {
+        return Some(HttpResponse::Conflict().body(
+            "A rebase is in progress in the workspace. Continue or abort it first.".to_string(),
+        ));
+    }
+    None
+}
+
+fn get_repository(workspace_path: &str, workspace_name: &str) -> Option<Repository> {
+    let workspace = PathBuf::from(workspace_path).join(workspace_name);
+    match Repository::open(workspace) {
+        Ok(r) => Some(r),
+        Err(e) => {
+            println!("Error while retrieving the repository: {:#?}", e);
+            None
+        }
+    }
+}
+
+fn checkout_branch(repository: &Repository, branch_name: &str) -> Result<(), git2::Error> {
+    repository.set_head(&format!("refs/heads/{}", branch_name))?;
+    repository.checkout_head(Some(
+        git2::build::CheckoutBuilder::default()
+            .remove_untracked(true)
+            .remove_ignored(true)
+            .force(),
+    ))
+}
+
+fn to_response(repository: &Repository, rebase: &mut Rebase) -> HttpResponse {
+    let branch = get_branch_name(rebase).unwrap_or_default();
+    match run_rebase(repository, rebase) {
+        Ok(RebaseOutcome::Completed(commit_uuid)) => HttpResponse::Ok().json(RebaseResult {
+            status: RebaseStatus::Completed,
+            branch,
+            commit_uuid,
+        }),
+        Ok(RebaseOutcome::Stopped(state)) => HttpResponse::Conflict().json(state),
+        Err(e) => {
+            eprintln!("Error while rebasing the branch {}: {:#?}", &branch, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+// Replays the remaining commits, and stops on the first commit that does not apply cleanly.
+fn run_rebase(repository: &Repository, rebase: &mut Rebase) -> Result<RebaseOutcome, git2::Error> {
+    while let Some(operation) = rebase.next() {
+        operation?;
+        if repository.index()?.has_conflicts() {
+            return Ok(RebaseOutcome::Stopped(get_rebase_state(
+                repository, rebase,
+            )?));
+        }
+        commit_operation(rebase)?;
+    }
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    rebase.finish(Some(&signature))?;
+    let commit = repository.head()?.peel_to_commit()?;
+    Ok(RebaseOutcome::Completed(commit.id().to_string()))
+}
+
+// The original author and message are kept. A commit whose changes are already on the new base
+// is dropped.
+fn commit_operation(rebase: &mut Rebase) -> Result<(), git2::Error> {
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    match rebase.commit(None, &signature, None) {
+        Ok(_) => Ok(()),
+        Err(e) if e.code() == ErrorCode::Applied => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+// While the rebase is stopped, HEAD is detached on the last replayed commit, or on the commit the
+// branch is rebased onto.
+fn is_rebase_head(repository: &Repository) -> Result<bool, git2::Error> {
+    if !repository.head_detached()? {
+        return Ok(false);
+    }
+    let head = repository.head()?.peel_to_commit()?.id();
+    let onto = fs::read_to_string(repository.path().join("rebase-merge").join("onto"))
+        .map_err(|e| git2::Error::from_str(&e.to_string()))?;
+    let onto = Oid::from_str(onto.trim())?;
+    Ok(head == onto || repository.graph_descendant_of(head, onto)?)
+}
+
+// The files of a stopped rebase are the ones the commit being replayed changes, which include
+// every conflicted file.
+fn is_rebase_file(
+    repository: &Repository,
+    rebase: &mut Rebase,
+    file_path: &str,
+) -> Result<bool, git2::Error> {
+    let commit = match rebase.operation_current().and_then(|n| rebase.nth(n)) {
+        Some(operation) => repository.find_commit(operation.id())?,
+        None => return Ok(false),
+    };
+    let path = Path::new(file_path);
+    let entry_id = |tree: &git2::Tree| tree.get_path(path).ok().map(|e| e.id());
+    let changed = entry_id(&commit.tree()?);
+    let original = match commit.parents().next() {
+        Some(parent) => entry_id(&parent.tree()?),
+        None => None,
+    };
+    Ok(changed != original)
+}
+
+fn get_branch_name(rebase: &Rebase) -> Option<String> {
+    rebase
+        .orig_head_name()
+        .map(|name| name.strip_prefix("refs/heads/").unwrap_or(name).to_string())
+}
+
+fn get_rebase_state(
+    repository: &Repository,
+    rebase: &mut Rebase,
+) -> Result<RebaseState, git2::Error> {
+    let operation = rebase.operation_current();
+    let commit_uuid = operation
+        .and_then(|n| rebase.nth(n))
+        .map(|o| o.id().to_string());
+    Ok(RebaseState {
+        branch: get_branch_name(rebase),
+        original_commit_uuid: rebase.orig_head_id().map(|oid| oid.to_string()),
+        commit_uuid,
+        operation: operation.map(|n| n + 1),
+        operations: rebase.len(),
+        conflicts: get_conflicts(&repository.index()?)?,
+    })
+}
@@ -0,0 +1,145 @@
+use crate::extractors::{BranchName, WorkspaceName};
+use crate::routes::{
+    check_direct_commits, check_rebase_in_progress, get_conflicts, update_branch, MergeConflict,
+};
+use actix_web::{web, HttpResponse};
+use git2::{BranchType, Commit, Index, Oid, Repository, Signature};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct RevertCommit {
+    pub commit_uuid: String,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct RevertResult {
+    commit_uuid: String,
+    reverted_commit_uuid: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct RevertConflicts {
+    reverted_commit_uuid: String,
+    branch: String,
+    conflicts: Vec<MergeConflict>,
+}
+
+// curl -X POST -H 'Content-Type: application/json' -d '{"commit_uuid":"commit_uuid"}' -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}/revert
+pub async fn revert_commit(
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
+    revert: web::Json<RevertCommit>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let workspace = PathBuf::from(workspace_path.as_str()).join(workspace_name.as_str());
+    let branch_name = branch_name.into_inner();
+    let commit_id = match Oid::from_str(&revert.commit_uuid) {
+        Ok(oid) => oid,
+        Err(_) => {
+            return HttpResponse::BadRequest().body(format!(
+                "The commit id {} is not valid.",
+                &revert.commit_uuid
+            ))
+        }
+    };
+    let repository = match Repository::open(&workspace) {
+        Ok(r) => r,
+        Err(e) => {
+            println!("Error while retrieving the repository: {:#?}", e);
+            return HttpResponse::NotFound().finish();
+        }
+    };
+    if let Some(response) = check_rebase_in_progress(&repository) {
+        return response;
+    }
+    let tip = match repository
+        .find_branch(&branch_name, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+    {
+        Ok(c) => c,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    if let Some(response) = check_direct_commits(&repository, &branch_name) {
+        return response;
+    }
+    let commit = match repository.find_commit(commit_id) {
+        Ok(c) => c,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    let is_in_branch = tip.id() == commit.id()
+        || repository
+            .graph_descendant_of(tip.id(), commit.id())
+            .unwrap_or(false);
+    if !is_in_branch {
+        return HttpResponse::BadRequest().body(format!(
+            "The commit {} is not part of the branch {}.",
+            commit.id(),
+            &branch_name
+        ));
+    }
+    if commit.parent_count() == 0 {
+        return HttpResponse::BadRequest().body(format!(
+            "The commit {} is the first commit of the history and cannot be reverted.",
+            commit.id()
+        ));
+    }
+
+    // Merge commits are reverted against their first parent, the branch they were merged into.
+    let mainline = if commit.parent_count() > 1 { 1 } else { 0 };
+    let mut index = match repository.revert_commit(&commit, &tip, mainline, None) {
+        Ok(i) => i,
+        Err(e) => {
+            eprintln!("Error while reverting the commit {}: {:#?}", commit.id(), e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    if index.has_conflicts() {
+        return match get_conflicts(&index) {
+            Ok(conflicts) => HttpResponse::Conflict().json(RevertConflicts {
+                reverted_commit_uuid: commit.id().to_string(),
+                branch: branch_name,
+                conflicts,
+            }),
+            Err(e) => {
+                eprintln!("Error while retrieving the revert conflicts: {:#?}", e);
+                HttpResponse::InternalServerError().finish()
+            }
+        };
+    }
+    let message = revert
+        .message
+        .clone()
+        .unwrap_or_else(|| get_revert_message(&commit));
+    match create_revert_commit(&repository, &mut index, &tip, &message)
+        .and_then(|oid| update_branch(&repository, &branch_name, oid, "revert").map(|_| oid))
+    {
+        Ok(oid) => HttpResponse::Ok().json(RevertResult {
+            commit_uuid: oid.to_string(),
+            reverted_commit_uuid: commit.id().to_string(),
+        }),
+        Err(e) => {
+            eprintln!("Error while creating the revert commit: {:#?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+fn get_revert_message(commit: &Commit) -> String {
+    format!(
+        "Revert \"{}\"\n\nThis reverts commit {}.",
+        commit.summary().unwrap_or_default(),
+        commit.id()
+    )
+}
+
+fn create_revert_commit(
+    repository: &Repository,
+    index: &mut Index,
+    parent: &Commit,
+    message: &str,
+) -> Result<Oid, git2::Error> {
+    let tree = repository.find_tree(index.write_tree_to(repository)?)?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    repository.commit(None, &signature, &signature, message, &tree, &[parent])
+}
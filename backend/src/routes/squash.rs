@@ -0,0 +1,222 @@
+use crate::extractors::{BranchName, WorkspaceName};
+use crate::routes::{
+    check_delete_or_reset, check_direct_commits, check_rebase_in_progress, create_backup,
+    update_branch, BranchBackup,
+};
+use actix_web::{web, HttpResponse};
+use git2::{BranchType, Commit, Oid, Repository, Signature};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct SquashCommits {
+    pub from: String,
+    pub to: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct SquashResult {
+    commit_uuid: String,
+    squash_commit_uuid: String,
+    squashed_commits: Vec<String>,
+    backup: BranchBackup,
+}
+
+// Squashes the commits from "from" to "to", both included, into a single commit. "to" defaults
+// to the tip of the branch, and the commits that follow it are replayed on the squash commit.
+// curl -X POST -H 'Content-Type: application/json' -d '{"from":"commit_uuid","to":"commit_uuid","message":"commit message"}' -v http://127.0.0.1:8000/workspaces/{workspace_name}/branches/{branch_name}/squash
+pub async fn squash_commits(
+    workspace_name: WorkspaceName,
+    branch_name: BranchName,
+    squash: web::Json<SquashCommits>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let workspace = PathBuf::from(workspace_path.as_str()).join(workspace_name.as_str());
+    let branch_name = branch_name.into_inner();
+    if squash.message.trim().is_empty() {
+        return HttpResponse::BadRequest().body("The commit message cannot be empty.");
+    }
+    let from = match Oid::from_str(&squash.from) {
+        Ok(oid) => oid,
+        Err(_) => {
+            return HttpResponse::BadRequest()
+                .body(format!("The commit id {} is not valid.", &squash.from))
+        }
+    };
+    let to = match squash.to.as_ref().map(|to| Oid::from_str(to)) {
+        Some(Ok(oid)) => Some(oid),
+        Some(Err(_)) => {
+            return HttpResponse::BadRequest().body(format!(
+                "The commit id {} is not valid.",
+                squash.to.as_deref().unwrap_or_default()
+            ))
+        }
+        None => None,
+    };
+    let repository = match Repository::open(&workspace) {
+        Ok(r) => r,
+        Err(e) => {
+            println!("Error while retrieving the repository: {:#?}", e);
+            return HttpResponse::NotFound().finish();
+        }
+    };
+    if let Some(response) = check_rebase_in_progress(&repository) {
+        return response;
+    }
+    let tip = match repository
+        .find_branch(&branch_name, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+    {
+        Ok(c) => c,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    // Squashing rewrites the history, so both protection rules refuse it.
+    if let Some(response) = check_delete_or_reset(&repository, &branch_name) {
+        return response;
+    }
+    if let Some(response) = check_direct_commits(&repository, &branch_name) {
+        return response;
+    }
+    let to = to.unwrap_or_else(|| tip.id());
+    let following_commits = match get_first_parent_chain(&repository, &tip, to) {
+        Some(commits) => commits,
+        None => {
+            return HttpResponse::BadRequest().body(format!(
+                "The commit {} is not part of the branch {}.",
+                to, &branch_name
+            ))
+        }
+    };
+    let to_commit = match repository.find_commit(to) {
+        Ok(c) => c,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    let squashed_commits = match get_first_parent_chain(&repository, &to_commit, from) {
+        Some(mut commits) => {
+            if let Ok(from_commit) = repository.find_commit(from) {
+                commits.push(from_commit);
+            }
+            commits
+        }
+        None => {
+            return HttpResponse::BadRequest().body(format!(
+                "The commit {} is not an ancestor of the commit {}.",
+                from, to
+            ))
+        }
+    };
+    if squashed_commits.len() < 2 {
+        return HttpResponse::BadRequest().body("At least two commits must be squashed.");
+    }
+    if squashed_commits.iter().any(|c| c.parent_count() > 1) {
+        return HttpResponse::BadRequest().body("Merge commits cannot be squashed.");
+    }
+
+    let backup = match create_backup(&repository, &branch_name, tip.id()) {
+        Ok(b) => b,
+        Err(e) => {
+            eprintln!(
+                "Error while creating the backup of the branch {}: {:#?}",
+                &branch_name, e
+            );
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    let squash_commit = match create_squash_commit(
+        &repository,
+        &squashed_commits[squashed_commits.len() - 1],
+        &to_commit,
+        &squash.message,
+    ) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Error while creating the squash commit: {:#?}", e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    let squash_commit_id = squash_commit.id();
+    match replay_commits(&repository, squash_commit, &following_commits).and_then(|new_tip| {
+        update_branch(&repository, &branch_name, new_tip, "squash").map(|_| new_tip)
+    }) {
+        Ok(new_tip) => HttpResponse::Ok().json(SquashResult {
+            commit_uuid: new_tip.to_string(),
+            squash_commit_uuid: squash_commit_id.to_string(),
+            squashed_commits: squashed_commits
+                .iter()
+                .rev()
+                .map(|c| c.id().to_string())
+                .collect(),
+            backup,
+        }),
+        Err(e) => {
+            eprintln!(
+                "Error while squashing the branch {}: {:#?}",
+                &branch_name, e
+            );
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+// Returns the commits met following the first parents from "start" until "end", "end" excluded,
+// or None when "end" is not on that path.
+fn get_first_parent_chain<'a>(
+    repository: &'a Repository,
+    start: &Commit<'a>,
+    end: Oid,
+) -> Option<Vec<Commit<'a>>> {
+    let mut commits = Vec::new();
+    let mut commit = start.clone();
+    while commit.id() != end {
+        let parent = repository.find_commit(commit.parent_id(0).ok()?).ok()?;
+        commits.push(commit);
+        commit = parent;
+    }
+    Some(commits)
+}
+
+// The squash commit has the tree of the newest commit and the parents of the oldest one.
+fn create_squash_commit<'a>(
+    repository: &'a Repository,
+    oldest: &Commit,
+    newest: &Commit,
+    message: &str,
+) -> Result<Commit<'a>, git2::Error> {
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    let parents: Vec<Commit> = oldest.parents().collect();
+    let parents: Vec<&Commit> = parents.iter().collect();
+    let oid = repository.commit(
+        None,
+        &signature,
+        &signature,
+        message,
+        &newest.tree()?,
+        &parents,
+    )?;
+    repository.find_commit(oid)
+}
+
+// The trees do not change, so the following commits are copied with their first parent replaced
+// and never conflict. "commits" goes from the newest to the oldest commit.
+fn replay_commits<'a>(
+    repository: &'a Repository,
+    base: Commit<'a>,
+    commits: &[Commit<'a>],
+) -> Result<Oid, git2::Error> {
+    let mut parent = base;
+    for commit in commits.iter().rev() {
+        let mut parents: Vec<Commit> = commit.parents().skip(1).collect();
+        parents.insert(0, parent);
+        let parents_refs: Vec<&Commit> = parents.iter().collect();
+        let oid = repository.commit(
+            None,
+            &commit.author(),
+            &commit.committer(),
+            commit.message().unwrap_or_default(),
+            &commit.tree()?,
+            &parents_refs,
+        )?;
+        parent = repository.find_commit(oid)?;
+    }
+    Ok(parent.id())
+}
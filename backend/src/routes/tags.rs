@@ -0,0 +1,160 @@
+use crate::extractors::{TagName, WorkspaceName};
+use crate::routes::{find_start_point, get_default_branch};
+use actix_web::{web, HttpResponse};
+use git2::{Reference, Repository, Signature};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct NewTag {
+    pub target: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct GitTag {
+    name: String,
+    commit_uuid: String,
+    message: Option<String>,
+    tagger: Option<String>,
+    time: Option<i64>,
+}
+
+// curl -X GET -v http://127.0.0.1:8000/workspaces/{workspace_name}/tags
+pub async fn retrieve_tags(
+    workspace_name: WorkspaceName,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let workspace = PathBuf::from(workspace_path.as_str()).join(workspace_name.as_str());
+    let repository = match Repository::open(&workspace) {
+        Ok(r) => r,
+        Err(e) => {
+            println!("Error while retrieving the repository: {:#?}", e);
+            return HttpResponse::NotFound().finish();
+        }
+    };
+    match get_tags(&repository) {
+        Ok(tags) => HttpResponse::Ok().json(tags),
+        Err(e) => {
+            eprintln!("Error while retrieving the tags: {:#?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+// curl -X POST -H 'Content-Type: application/json' -d '{"target":"master","message":"First edition"}' -v http://127.0.0.1:8000/workspaces/{workspace_name}/tags/{tag_name}
+pub async fn create_tag(
+    workspace_name: WorkspaceName,
+    tag_name: TagName,
+    new_tag: web::Json<NewTag>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let workspace = PathBuf::from(workspace_path.as_str()).join(workspace_name.as_str());
+    let tag_name = tag_name.into_inner();
+    if new_tag.message.trim().is_empty() {
+        return HttpResponse::BadRequest().body("The tag message cannot be empty.");
+    }
+    let repository = match Repository::open(&workspace) {
+        Ok(r) => r,
+        Err(e) => {
+            println!("Error while retrieving the repository: {:#?}", e);
+            return HttpResponse::NotFound().finish();
+        }
+    };
+    if repository
+        .find_reference(&format!("refs/tags/{}", tag_name))
+        .is_ok()
+    {
+        return HttpResponse::Conflict().body(format!("The tag {} already exists.", tag_name));
+    }
+    // The tag points to the tip of the default branch when no target is given.
+    let target = new_tag
+        .target
+        .clone()
+        .unwrap_or_else(|| get_default_branch(&repository));
+    let commit = match find_start_point(&repository, &target) {
+        Some(c) => c,
+        None => {
+            return HttpResponse::NotFound().body(format!("The target {} does not exist.", target))
+        }
+    };
+    let tag = Signature::now("Marco Cella", "marco.cella.tv@gmail.com").and_then(|signature| {
+        repository.tag(
+            &tag_name,
+            commit.as_object(),
+            &signature,
+            &new_tag.message,
+            false,
+        )
+    });
+    let tag = tag
+        .and_then(|_| repository.find_reference(&format!("refs/tags/{}", tag_name)))
+        .and_then(|reference| get_tag(&tag_name, &reference));
+    match tag {
+        Ok(tag) => HttpResponse::Ok().json(tag),
+        Err(e) => {
+            eprintln!("Error while creating the tag {}: {:#?}", &tag_name, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+// curl -X DELETE -v http://127.0.0.1:8000/workspaces/{workspace_name}/tags/{tag_name}
+pub async fn delete_tag(
+    workspace_name: WorkspaceName,
+    tag_name: TagName,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let workspace = PathBuf::from(workspace_path.as_str()).join(workspace_name.as_str());
+    let tag_name = tag_name.into_inner();
+    let repository = match Repository::open(&workspace) {
+        Ok(r) => r,
+        Err(e) => {
+            println!("Error while retrieving the repository: {:#?}", e);
+            return HttpResponse::NotFound().finish();
+        }
+    };
+    let tag = match repository
+        .find_reference(&format!("refs/tags/{}", tag_name))
+        .and_then(|r| get_tag(&tag_name, &r))
+    {
+        Ok(t) => t,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    match repository.tag_delete(&tag_name) {
+        Ok(()) => HttpResponse::Ok().json(tag),
+        Err(e) => {
+            eprintln!("Error while deleting the tag {}: {:#?}", &tag_name, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+fn get_tags(repository: &Repository) -> Result<Vec<GitTag>, git2::Error> {
+    let mut tags = Vec::new();
+    for tag_name in repository.tag_names(None)?.iter().flatten() {
+        let reference = repository.find_reference(&format!("refs/tags/{}", tag_name))?;
+        tags.push(get_tag(tag_name, &reference)?);
+    }
+    tags.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(tags)
+}
+
+// Lightweight tags, created outside of the application, have no message and no tagger.
+fn get_tag(tag_name: &str, reference: &Reference) -> Result<GitTag, git2::Error> {
+    let commit = reference.peel_to_commit()?;
+    let (message, tagger, time) = match reference.peel_to_tag() {
+        Ok(tag) => (
+            tag.message().map(|m| m.to_string()),
+            tag.tagger().and_then(|t| t.name().map(|n| n.to_string())),
+            tag.tagger().map(|t| t.when().seconds()),
+        ),
+        Err(_) => (None, None, None),
+    };
+    Ok(GitTag {
+        name: tag_name.to_string(),
+        commit_uuid: commit.id().to_string(),
+        message,
+        tagger,
+        time,
+    })
+}
@@ -0,0 +1,185 @@
+use crate::configuration::Settings;
+use actix_web::{web, HttpResponse};
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const TRASH_DIRECTORY: &str = ".trash";
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct TrashEntry {
+    pub id: String,
+    pub name: String,
+    pub deleted_at: u64,
+    pub purge_at: u64,
+}
+
+// curl -X GET -v http://127.0.0.1:8000/trash
+pub async fn retrieve_trash(
+    workspace_path: web::Data<String>,
+    settings: web::Data<Settings>,
+) -> HttpResponse {
+    let trash_directory = get_trash_directory(workspace_path.as_str());
+    match get_trash_entries(&trash_directory, get_retention(&settings)) {
+        Ok(mut entries) => {
+            entries.sort_by_key(|e| Reverse(e.deleted_at));
+            HttpResponse::Ok().json(entries)
+        }
+        Err(e) => {
+            eprintln!("Error while retrieving the trash: {:#?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+// curl -X POST -v http://127.0.0.1:8000/trash/{trash_id}/restore
+pub async fn restore_workspace(
+    trash_id_param: web::Path<String>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let trash_id = trash_id_param.into_inner();
+    let trash_directory = get_trash_directory(workspace_path.as_str());
+    let (_, workspace_name) = match parse_trash_id(&trash_id) {
+        Some(t) => t,
+        None => return HttpResponse::NotFound().finish(),
+    };
+    let trashed_workspace = trash_directory.join(&trash_id);
+    if !trashed_workspace.is_dir() {
+        return HttpResponse::NotFound().finish();
+    }
+    let workspace = PathBuf::from(workspace_path.as_str()).join(workspace_name);
+    if workspace.exists() {
+        return HttpResponse::Conflict().finish();
+    }
+
+    if let Err(e) = fs::rename(&trashed_workspace, &workspace) {
+        eprintln!("Error while restoring the repository: {:#?}", e);
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().finish()
+}
+
+// curl -X DELETE -v http://127.0.0.1:8000/trash/{trash_id}
+pub async fn purge_workspace(
+    trash_id_param: web::Path<String>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let trash_id = trash_id_param.into_inner();
+    let trash_directory = get_trash_directory(workspace_path.as_str());
+    if parse_trash_id(&trash_id).is_none() {
+        return HttpResponse::NotFound().finish();
+    }
+    let trashed_workspace = trash_directory.join(&trash_id);
+    if !trashed_workspace.is_dir() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    if let Err(e) = fs::remove_dir_all(&trashed_workspace) {
+        eprintln!("Error while purging the repository: {:#?}", e);
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().finish()
+}
+
+pub fn get_trash_directory(workspaces_path: &str) -> PathBuf {
+    Path::new(workspaces_path).join(TRASH_DIRECTORY)
+}
+
+// Trashed workspaces are stored as "{deleted_at}-{workspace_name}" so that the same workspace
+// name can be deleted more than once and the deletion time survives a server restart.
+pub fn move_to_trash(
+    workspace: &Path,
+    workspaces_path: &str,
+    retention: Duration,
+) -> std::io::Result<TrashEntry> {
+    let trash_directory = get_trash_directory(workspaces_path);
+    fs::create_dir_all(&trash_directory)?;
+    let name = workspace
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_string();
+    let mut deleted_at = now();
+    while trash_directory
+        .join(format!("{}-{}", deleted_at, &name))
+        .exists()
+    {
+        deleted_at += 1;
+    }
+    let id = format!("{}-{}", deleted_at, &name);
+    fs::rename(workspace, trash_directory.join(&id))?;
+    Ok(TrashEntry {
+        id,
+        name,
+        deleted_at,
+        purge_at: deleted_at + retention.as_secs(),
+    })
+}
+
+pub fn purge_expired_workspaces(trash_directory: &Path, retention: Duration) {
+    let entries = match get_trash_entries(trash_directory, retention) {
+        Ok(e) => e,
+        Err(e) => {
+            eprintln!("Error while retrieving the trash: {:#?}", e);
+            return;
+        }
+    };
+    let now = now();
+    for entry in entries.iter().filter(|e| e.purge_at <= now) {
+        if let Err(e) = fs::remove_dir_all(trash_directory.join(&entry.id)) {
+            eprintln!("Error while purging the repository {}: {:#?}", &entry.id, e);
+        }
+    }
+}
+
+pub fn get_retention(settings: &Settings) -> Duration {
+    Duration::from_secs(settings.trash_retention_days * 24 * 60 * 60)
+}
+
+fn get_trash_entries(
+    trash_directory: &Path,
+    retention: Duration,
+) -> std::io::Result<Vec<TrashEntry>> {
+    if !trash_directory.exists() {
+        return Ok(Vec::new());
+    }
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(trash_directory)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let id = match path.file_name().and_then(|n| n.to_str()) {
+            Some(id) => id.to_string(),
+            None => continue,
+        };
+        if let Some((deleted_at, name)) = parse_trash_id(&id) {
+            entries.push(TrashEntry {
+                name: name.to_string(),
+                deleted_at,
+                purge_at: deleted_at + retention.as_secs(),
+                id,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+fn parse_trash_id(trash_id: &str) -> Option<(u64, &str)> {
+    let (deleted_at, name) = trash_id.split_once('-')?;
+    let deleted_at = deleted_at.parse::<u64>().ok()?;
+    if name.is_empty() || name.contains('/') || name == "." || name == ".." {
+        return None;
+    }
+    Some((deleted_at, name))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
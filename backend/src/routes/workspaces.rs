@@ -1,41 +1,260 @@
+use super::trash::{get_retention, move_to_trash, TRASH_DIRECTORY};
+use crate::configuration::{Settings, TemplateSettings};
+use crate::extractors::{BranchName, OptionalJson, WorkspaceName};
+use crate::routes::get_default_branch;
 use actix_web::{web, HttpResponse};
-use git2::{Commit, IndexAddOption, ObjectType, Repository, Signature};
-use std::{fs, path::PathBuf};
+use git2::{BranchType, IndexAddOption, Repository, Signature};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use walkdir::WalkDir;
 
-// curl -X GET -v http://127.0.0.1:8000/workspaces
-pub async fn retrieve_workspaces(workspace_path: web::Data<String>) -> HttpResponse {
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct Workspace {
+    name: String,
+    description: Option<String>,
+    owner: Option<String>,
+    labels: Vec<String>,
+    created_at: Option<i64>,
+    updated_at: Option<i64>,
+    forked_from: Option<String>,
+    branch_count: usize,
+    last_commit: Option<WorkspaceCommit>,
+    size: u64,
+}
+
+impl Workspace {
+    fn new(name: String) -> Workspace {
+        Workspace {
+            name,
+            description: None,
+            owner: None,
+            labels: Vec::new(),
+            created_at: None,
+            updated_at: None,
+            forked_from: None,
+            branch_count: 0,
+            last_commit: None,
+            size: 0,
+        }
+    }
+}
+
+// The size walks the whole workspace directory and the commit details peel every branch, so the
+// workspace list computes each part only when it needs it.
+#[derive(Debug, Clone, Copy)]
+struct DetailParts {
+    metadata: bool,
+    commits: bool,
+    size: bool,
+}
+
+impl DetailParts {
+    const ALL: DetailParts = DetailParts {
+        metadata: true,
+        commits: true,
+        size: true,
+    };
+
+    fn remaining(self) -> DetailParts {
+        DetailParts {
+            metadata: !self.metadata,
+            commits: !self.commits,
+            size: !self.size,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct WorkspaceCommit {
+    commit_uuid: String,
+    time: i64,
+    author: String,
+    message: String,
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct WorkspaceMetadata {
+    pub description: Option<String>,
+    pub owner: Option<String>,
+    pub labels: Option<Vec<String>>,
+}
+
+// Unknown fields are refused, so that a mistyped "template" is not silently replaced by the
+// default template.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct NewWorkspace {
+    pub description: Option<String>,
+    pub owner: Option<String>,
+    pub labels: Option<Vec<String>>,
+    pub template: Option<String>,
+}
+
+impl NewWorkspace {
+    pub fn metadata(&self) -> WorkspaceMetadata {
+        WorkspaceMetadata {
+            description: self.description.clone(),
+            owner: self.owner.clone(),
+            labels: self.labels.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspacesSort {
+    #[default]
+    Name,
+    LastCommit,
+    Size,
+    Created,
+}
+
+#[derive(Debug, Clone, Copy, Default, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct WorkspacesQuery {
+    pub label: Option<String>,
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub sort: WorkspacesSort,
+    #[serde(default)]
+    pub order: SortOrder,
+    pub limit: Option<usize>,
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct RenameWorkspace {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ForkWorkspace {
+    pub name: String,
+    pub branch: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct ForkedWorkspace {
+    name: String,
+    forked_from: String,
+    forked_branch: Option<String>,
+}
+
+// Only the details the label filter and the sort need are computed for every workspace, and the
+// rest only for the returned page.
+// curl -X GET -v "http://127.0.0.1:8000/workspaces?label={label}&prefix={prefix}&sort=last_commit&order=desc&limit=20&cursor={cursor}"
+pub async fn retrieve_workspaces(
+    query: web::Query<WorkspacesQuery>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
     let workspace_directory = PathBuf::from(&workspace_path.as_str());
-    let mut vec: Vec<String> = Vec::new();
-    match fs::read_dir(workspace_directory) {
+    let parts = DetailParts {
+        metadata: query.label.is_some() || matches!(query.sort, WorkspacesSort::Created),
+        commits: matches!(query.sort, WorkspacesSort::LastCommit),
+        size: matches!(query.sort, WorkspacesSort::Size),
+    };
+    let mut vec: Vec<Workspace> = Vec::new();
+    match fs::read_dir(&workspace_directory) {
         Ok(a) => a
             .map(|res| res.map(|e| e.path()))
             .filter_map(Result::ok)
-            .filter(|f| f.is_dir())
-            .for_each(|f| match f.file_name() {
-                Some(n) => {
-                    let p = n.clone().to_owned();
-                    vec.push(p.into_string().unwrap());
+            .filter(|f| f.is_dir() && !f.ends_with(TRASH_DIRECTORY))
+            .for_each(|f| {
+                if let Some(n) = f.file_name() {
+                    let name = n.to_owned().into_string().unwrap();
+                    if let Some(prefix) = &query.prefix {
+                        if !name.starts_with(prefix.as_str()) {
+                            return;
+                        }
+                    }
+                    let mut details = Workspace::new(name);
+                    add_workspace_details(&mut details, &f, parts);
+                    vec.push(details);
                 }
-                None => {}
             }),
         Err(_) => return HttpResponse::InternalServerError().finish(),
     };
-    HttpResponse::Ok().json(vec)
+    if let Some(label) = &query.label {
+        vec.retain(|w| w.labels.contains(label));
+    }
+
+    sort_workspaces(&mut vec, query.sort, query.order);
+
+    // The cursor is the name of the last workspace of the previous page.
+    let start = match &query.cursor {
+        Some(cursor) => match vec.iter().position(|w| &w.name == cursor) {
+            Some(position) => position + 1,
+            None => return HttpResponse::BadRequest().body("The cursor is not valid."),
+        },
+        None => 0,
+    };
+    let end = match query.limit {
+        Some(limit) => (start + limit).min(vec.len()),
+        None => vec.len(),
+    };
+    let mut page = vec[start..end].to_vec();
+    for details in page.iter_mut() {
+        let workspace = workspace_directory.join(&details.name);
+        add_workspace_details(details, &workspace, parts.remaining());
+    }
+    let mut response = HttpResponse::Ok();
+    if end < vec.len() {
+        if let Some(last) = page.last() {
+            response.insert_header(("X-Next-Cursor", last.name.clone()));
+        }
+    }
+    response.json(page)
 }
 
-// curl -X POST -v http://127.0.0.1:8000/workspaces/{workspace_name}
+// curl -X POST -H 'Content-Type: application/json' -d '{"description":"description","owner":"owner","labels":["label"],"template":"default"}' -v http://127.0.0.1:8000/workspaces/{workspace_name}
 pub async fn create_workspace(
-    workspace_name_param: web::Path<String>,
+    workspace_name: WorkspaceName,
+    new_workspace: OptionalJson<NewWorkspace>,
     workspace_path: web::Data<String>,
+    settings: web::Data<Settings>,
 ) -> HttpResponse {
-    let workspace_name = workspace_name_param.into_inner();
+    let workspace_name = workspace_name.into_inner();
     let workspace_directory = PathBuf::from(&workspace_path.as_str());
     let workspace = get_workspace(&workspace_name, &workspace_directory);
+    let new_workspace = new_workspace.into_inner();
 
     if workspace.exists() {
         return HttpResponse::Conflict().finish();
     }
 
+    let template = match new_workspace
+        .template
+        .as_ref()
+        .or(settings.default_template.as_ref())
+    {
+        Some(template_name) => match settings.templates.get(template_name) {
+            Some(t) => Some(t),
+            None => {
+                return HttpResponse::BadRequest()
+                    .body(format!("The template {} does not exist.", template_name))
+            }
+        },
+        None => None,
+    };
+    if let Some(t) = template {
+        if !PathBuf::from(&t.path).is_dir() {
+            eprintln!("The template directory {} does not exist.", &t.path);
+            return HttpResponse::InternalServerError().body(format!(
+                "The template directory {} does not exist.",
+                &t.path
+            ));
+        }
+    }
+
     let repository = match create_git_repository(&workspace) {
         Ok(r) => r,
         Err(e) => {
@@ -44,20 +263,58 @@ pub async fn create_workspace(
         }
     };
 
-    if let Err(e) = create_master_branch(repository, &workspace) {
-        eprintln!("Error while creating the master branch: {:#?}", e);
+    if let Err(e) = set_workspace_metadata(&repository, &new_workspace.metadata(), true) {
+        eprintln!("Error while storing the workspace metadata: {:#?}", e);
+        _ = fs::remove_dir_all(workspace.as_path());
         return HttpResponse::InternalServerError().finish();
     }
 
+    if let Err(e) = create_master_branch(&repository, &workspace, template) {
+        eprintln!("Error while creating the master branch: {}", e);
+        _ = fs::remove_dir_all(workspace.as_path());
+        return HttpResponse::InternalServerError().body(e.to_string());
+    }
+
     HttpResponse::Ok().finish()
 }
 
+// curl -X PATCH -H 'Content-Type: application/json' -d '{"description":"description","owner":"owner","labels":["label"]}' -v http://127.0.0.1:8000/workspaces/{workspace_name}
+pub async fn update_workspace(
+    workspace_name: WorkspaceName,
+    metadata: web::Json<WorkspaceMetadata>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let workspace_name = workspace_name.into_inner();
+    let workspace_directory = PathBuf::from(&workspace_path.as_str());
+    let workspace = get_workspace(&workspace_name, &workspace_directory);
+
+    if !workspace.exists() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let repository = match get_repository(&workspace) {
+        Ok(r) => r,
+        Err(e) => {
+            println!("Error while retrieving the repository: {:#?}", e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    if let Err(e) = set_workspace_metadata(&repository, &metadata, false) {
+        eprintln!("Error while storing the workspace metadata: {:#?}", e);
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(get_workspace_details(workspace_name, &workspace))
+}
+
 // curl -X DELETE -v http://127.0.0.1:8000/workspaces/{workspace_name}
 pub async fn delete_workspace(
-    workspace_name_param: web::Path<String>,
+    workspace_name: WorkspaceName,
     workspace_path: web::Data<String>,
+    settings: web::Data<Settings>,
 ) -> HttpResponse {
-    let workspace_name = workspace_name_param.into_inner();
+    let workspace_name = workspace_name.into_inner();
     let workspace_directory = PathBuf::from(&workspace_path.as_str());
     let workspace = get_workspace(&workspace_name, &workspace_directory);
 
@@ -65,14 +322,117 @@ pub async fn delete_workspace(
         return HttpResponse::NotFound().finish();
     }
 
-    if let Err(e) = fs::remove_dir_all(workspace.as_path()) {
-        println!("Error while deleting the repository: {:#?}", e);
+    match move_to_trash(
+        &workspace,
+        workspace_path.as_str(),
+        get_retention(&settings),
+    ) {
+        Ok(trash_entry) => HttpResponse::Ok().json(trash_entry),
+        Err(e) => {
+            println!("Error while deleting the repository: {:#?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+// curl -X PUT -H 'Content-Type: application/json' -d '{"name":"new_workspace_name"}' -v http://127.0.0.1:8000/workspaces/{workspace_name}
+pub async fn rename_workspace(
+    workspace_name: WorkspaceName,
+    rename: web::Json<RenameWorkspace>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let workspace_name = workspace_name.into_inner();
+    let workspace_directory = PathBuf::from(&workspace_path.as_str());
+    let workspace = get_workspace(&workspace_name, &workspace_directory);
+    let new_workspace_name = match WorkspaceName::parse(&rename.name) {
+        Ok(n) => n,
+        Err(reason) => return HttpResponse::BadRequest().body(reason),
+    };
+    let new_workspace = get_workspace(&new_workspace_name, &workspace_directory);
+
+    if !workspace.exists() {
+        return HttpResponse::NotFound().finish();
+    }
+    if new_workspace.exists() {
+        return HttpResponse::Conflict().finish();
+    }
+
+    // A single rename moves the .git directory together with the files, keeping the history.
+    if let Err(e) = fs::rename(workspace.as_path(), new_workspace.as_path()) {
+        eprintln!("Error while renaming the repository: {:#?}", e);
         return HttpResponse::InternalServerError().finish();
     }
 
     HttpResponse::Ok().finish()
 }
 
+// curl -X POST -H 'Content-Type: application/json' -d '{"name":"new_workspace_name","branch":"master"}' -v http://127.0.0.1:8000/workspaces/{workspace_name}/fork
+pub async fn fork_workspace(
+    workspace_name: WorkspaceName,
+    fork: web::Json<ForkWorkspace>,
+    workspace_path: web::Data<String>,
+) -> HttpResponse {
+    let workspace_name = workspace_name.into_inner();
+    let workspace_directory = PathBuf::from(&workspace_path.as_str());
+    let workspace = get_workspace(&workspace_name, &workspace_directory);
+    let new_workspace_name = match WorkspaceName::parse(&fork.name) {
+        Ok(n) => n,
+        Err(reason) => return HttpResponse::BadRequest().body(reason),
+    };
+    if let Some(Err(reason)) = fork.branch.as_deref().map(BranchName::parse) {
+        return HttpResponse::BadRequest().body(reason);
+    }
+    let new_workspace = get_workspace(&new_workspace_name, &workspace_directory);
+
+    if !workspace.exists() {
+        return HttpResponse::NotFound().finish();
+    }
+    if new_workspace.exists() {
+        return HttpResponse::Conflict().finish();
+    }
+
+    let source_repository = match get_repository(&workspace) {
+        Ok(r) => r,
+        Err(e) => {
+            println!("Error while retrieving the repository: {:#?}", e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    if let Some(branch_name) = &fork.branch {
+        if source_repository
+            .find_branch(branch_name, BranchType::Local)
+            .is_err()
+        {
+            return HttpResponse::NotFound().finish();
+        }
+    }
+    let head_branch_name = match get_fork_head_branch(&source_repository, fork.branch.as_deref()) {
+        Some(b) => b,
+        None => {
+            return HttpResponse::Conflict()
+                .body("The workspace has no branch that the fork can check out.")
+        }
+    };
+
+    if let Err(e) = fork_git_repository(
+        &source_repository,
+        &workspace_name,
+        &new_workspace,
+        fork.branch.as_deref(),
+        &head_branch_name,
+    ) {
+        eprintln!("Error while forking the repository: {:#?}", e);
+        _ = fs::remove_dir_all(new_workspace.as_path());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(ForkedWorkspace {
+        name: fork.name.clone(),
+        forked_from: workspace_name,
+        forked_branch: fork.branch.clone(),
+    })
+}
+
 fn get_workspace(workspace_name: &String, workspace_directory: &PathBuf) -> PathBuf {
     let mut workspace = PathBuf::from(&workspace_directory);
     workspace.push(&workspace_name);
@@ -97,69 +457,250 @@ fn get_repository(workspace: &PathBuf) -> Result<Repository, Box<dyn std::error:
     }
 }
 
-fn create_master_branch(repository: Repository, workspace: &PathBuf) -> Result<(), git2::Error> {
-    create_first_commit(&repository)?;
-    if let Err(e) = copy_file(&workspace) {
-        eprintln!("Error while creating the README.md file: {:#?}", e);
+// The fork checks out the forked branch, or the branch of the source's HEAD. A detached or unborn
+// HEAD falls back to the source's default branch, as long as it exists.
+fn get_fork_head_branch(
+    source_repository: &Repository,
+    branch_name: Option<&str>,
+) -> Option<String> {
+    if let Some(b) = branch_name {
+        return Some(b.to_string());
+    }
+    if let Ok(head) = source_repository.head() {
+        if head.is_branch() {
+            return head.shorthand().map(|s| s.to_string());
+        }
     }
-    let first_commit = find_last_commit(&repository)?;
-    create_second_commit(&repository, &first_commit)?;
-    let (object, reference) = repository.revparse_ext("master")?;
-    match reference {
-        Some(gref) => repository.set_head(gref.name().unwrap())?,
-        None => repository.set_head_detached(object.id())?,
+    let default_branch = get_default_branch(source_repository);
+    source_repository
+        .find_branch(&default_branch, BranchType::Local)
+        .ok()
+        .map(|_| default_branch)
+}
+
+fn fork_git_repository(
+    source_repository: &Repository,
+    source_workspace_name: &str,
+    workspace: &PathBuf,
+    branch_name: Option<&str>,
+    head_branch_name: &str,
+) -> Result<(), git2::Error> {
+    let source_path = source_repository.path().to_str().unwrap().to_string();
+    let repository = Repository::init(workspace)?;
+    let refspecs = match branch_name {
+        Some(b) => vec![format!("+refs/heads/{}:refs/heads/{}", b, b)],
+        None => vec![
+            "+refs/heads/*:refs/heads/*".to_string(),
+            "+refs/tags/*:refs/tags/*".to_string(),
+        ],
+    };
+    repository
+        .remote_anonymous(&source_path)?
+        .fetch(&refspecs, None, None)?;
+
+    repository.set_head(&format!("refs/heads/{}", head_branch_name))?;
+    repository.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))?;
+    // A fork of a single branch uses it as default branch, otherwise the source's one is kept.
+    let default_branch = match branch_name {
+        Some(b) => b.to_string(),
+        None => get_default_branch(source_repository),
     };
+
+    let mut config = repository.config()?;
+    config.set_str("docversions.defaultbranch", &default_branch)?;
+    config.set_i64("docversions.created", now())?;
+    config.set_i64("docversions.updated", now())?;
+    config.set_str("docversions.forkedfrom", source_workspace_name)?;
+    if let Some(b) = branch_name {
+        config.set_str("docversions.forkedbranch", b)?;
+    }
     Ok(())
 }
 
-fn copy_file(workspace_directory: &PathBuf) -> std::io::Result<u64> {
-    let mut file_path = workspace_directory.clone();
-    file_path.push("README.md");
-    std::fs::copy("worspace_README.md", &file_path)
+fn get_workspace_details(name: String, workspace: &Path) -> Workspace {
+    let mut details = Workspace::new(name);
+    add_workspace_details(&mut details, workspace, DetailParts::ALL);
+    details
 }
 
-fn create_first_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
-    let mut index = repository.index()?;
-    let oid = index.write_tree()?;
-    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+fn add_workspace_details(details: &mut Workspace, workspace: &Path, parts: DetailParts) {
+    if parts.size {
+        details.size = get_directory_size(workspace);
+    }
+    if !parts.metadata && !parts.commits {
+        return;
+    }
+    let repository = match Repository::open(workspace) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    if parts.commits {
+        add_commit_details(details, &repository);
+    }
+    if !parts.metadata {
+        return;
+    }
+    let config = match repository.config().and_then(|mut c| c.snapshot()) {
+        Ok(c) => c,
+        Err(_) => return,
+    };
+    details.description = config.get_string("docversions.description").ok();
+    details.owner = config.get_string("docversions.owner").ok();
+    details.created_at = config.get_i64("docversions.created").ok();
+    details.updated_at = config.get_i64("docversions.updated").ok();
+    details.forked_from = config.get_string("docversions.forkedfrom").ok();
+    if let Ok(mut entries) = config.multivar("docversions.label", None) {
+        while let Some(Ok(entry)) = entries.next() {
+            if let Some(label) = entry.value() {
+                details.labels.push(label.to_string());
+            }
+        }
+    };
+}
 
-    let tree = repository.find_tree(oid)?;
+fn add_commit_details(details: &mut Workspace, repository: &Repository) {
+    let branches = match repository.branches(Some(BranchType::Local)) {
+        Ok(b) => b,
+        Err(_) => return,
+    };
+    for (branch, _) in branches.filter_map(Result::ok) {
+        details.branch_count += 1;
+        let commit = match branch.get().peel_to_commit() {
+            Ok(c) => c,
+            Err(_) => continue,
+        };
+        let last_commit_time = details.last_commit.as_ref().map(|c| c.time);
+        if last_commit_time.unwrap_or(i64::MIN) < commit.time().seconds() {
+            details.last_commit = Some(WorkspaceCommit {
+                commit_uuid: commit.id().to_string(),
+                time: commit.time().seconds(),
+                author: commit.author().name().unwrap_or_default().to_string(),
+                message: commit.message().unwrap_or_default().to_string(),
+            });
+        }
+    }
+}
 
-    repository.commit(
-        Some("HEAD"),
-        &signature,
-        &signature,
-        "Initial commit",
-        &tree,
-        &[],
-    )
+fn get_directory_size(directory: &Path) -> u64 {
+    WalkDir::new(directory)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
 }
 
-fn find_last_commit(repo: &Repository) -> Result<Commit, git2::Error> {
-    let obj = repo.head()?.resolve()?.peel(ObjectType::Commit)?;
-    obj.into_commit()
-        .map_err(|_| git2::Error::from_str("Couldn't find commit"))
+fn sort_workspaces(workspaces: &mut [Workspace], sort: WorkspacesSort, order: SortOrder) {
+    workspaces.sort_by(|a, b| {
+        let ordering = match sort {
+            WorkspacesSort::Name => a.name.cmp(&b.name),
+            WorkspacesSort::LastCommit => a
+                .last_commit
+                .as_ref()
+                .map(|c| c.time)
+                .cmp(&b.last_commit.as_ref().map(|c| c.time)),
+            WorkspacesSort::Size => a.size.cmp(&b.size),
+            WorkspacesSort::Created => a.created_at.cmp(&b.created_at),
+        };
+        let ordering = match order {
+            SortOrder::Asc => ordering,
+            SortOrder::Desc => ordering.reverse(),
+        };
+        ordering.then_with(|| a.name.cmp(&b.name))
+    });
 }
 
-fn create_second_commit(
+fn set_workspace_metadata(
     repository: &Repository,
-    first_commit: &Commit,
-) -> Result<git2::Oid, git2::Error> {
-    let mut index = repository.index()?;
+    metadata: &WorkspaceMetadata,
+    created: bool,
+) -> Result<(), git2::Error> {
+    let mut config = repository.config()?;
+    let now = now();
+    if created {
+        config.set_i64("docversions.created", now)?;
+    }
+    config.set_i64("docversions.updated", now)?;
+    if let Some(description) = &metadata.description {
+        config.set_str("docversions.description", description)?;
+    }
+    if let Some(owner) = &metadata.owner {
+        config.set_str("docversions.owner", owner)?;
+    }
+    if let Some(labels) = &metadata.labels {
+        match config.remove_multivar("docversions.label", ".*") {
+            Err(e) if e.code() != git2::ErrorCode::NotFound => return Err(e),
+            _ => {}
+        }
+        for label in labels {
+            config.set_multivar("docversions.label", "^$", label)?;
+        }
+    }
+    Ok(())
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+// The errors name the step that failed, since they are returned to the client.
+fn create_master_branch(
+    repository: &Repository,
+    workspace: &Path,
+    template: Option<&TemplateSettings>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let commit_message = match template {
+        Some(t) => {
+            copy_template(&t.path, workspace)
+                .map_err(|e| format!("Error while applying the workspace template: {}", e))?;
+            t.commit_message.as_str()
+        }
+        None => "Initial commit",
+    };
+    create_initial_commit(repository, commit_message)
+        .map_err(|e| format!("Error while creating the initial commit: {}", e))?;
+    Ok(())
+}
 
+fn create_initial_commit(repository: &Repository, commit_message: &str) -> Result<(), git2::Error> {
+    let mut index = repository.index()?;
     index.add_all(["*"].iter(), IndexAddOption::DEFAULT, None)?;
     index.write()?;
     let oid = index.write_tree()?;
     let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
-
     let tree = repository.find_tree(oid)?;
-
     repository.commit(
-        Some("HEAD"),
+        Some("refs/heads/master"),
         &signature,
         &signature,
-        "Second commit",
+        commit_message,
         &tree,
-        &[first_commit],
-    )
+        &[],
+    )?;
+    repository.set_head("refs/heads/master")?;
+    repository
+        .config()?
+        .set_str("docversions.defaultbranch", "master")
+}
+
+fn copy_template(template_path: &str, workspace: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let template_directory = Path::new(template_path);
+    for entry in WalkDir::new(template_directory)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|e| e.file_name() != ".git")
+    {
+        let entry = entry?;
+        let destination = workspace.join(entry.path().strip_prefix(template_directory)?);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&destination)?;
+        } else {
+            fs::copy(entry.path(), &destination)?;
+        }
+    }
+    Ok(())
 }
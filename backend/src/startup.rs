@@ -1,8 +1,17 @@
 use crate::configuration::Settings;
 use crate::routes::{
-    create_branches, create_file, create_workspace, delete_file, delete_workspace, get_branch_logs,
-    health_check, retrieve_branches, retrieve_file_content, retrieve_files_status,
-    retrieve_workspaces, set_current_branch, update_file,
+    abort_merge_session, abort_rebase, cherry_pick_commits, commit_merge_session, compare_branches,
+    continue_rebase, create_branches, create_file, create_tag, create_workspace, delete_branch,
+    delete_file, delete_tag, delete_workspace, export_workspace, fork_workspace, get_branch_logs,
+    get_retention, get_trash_directory, get_upload_form_config, health_check, import_workspace,
+    merge_branch, purge_expired_workspaces, purge_workspace, rebase_branch, rename_branch,
+    rename_workspace, reset_branch, resolve_conflict_file, resolve_conflict_file_as_deleted,
+    resolve_rebase_file, resolve_rebase_file_as_deleted, restore_branch_backup, restore_workspace,
+    retrieve_branch_backups, retrieve_branch_protection, retrieve_branches, retrieve_conflict_file,
+    retrieve_file_content, retrieve_files_status, retrieve_merge_session, retrieve_merge_sessions,
+    retrieve_rebase, retrieve_tags, retrieve_trash, retrieve_workspace_usage, retrieve_workspaces,
+    revert_commit, set_current_branch, squash_commits, update_branch_protection, update_file,
+    update_workspace,
 };
 use actix_web::dev::Server;
 use actix_web::web::ServiceConfig;
@@ -10,14 +19,17 @@ use actix_web::{web, App, HttpServer};
 use std::fs;
 use std::net::TcpListener;
 use std::path::Path;
+use std::time::Duration;
 
 pub fn run(listener: TcpListener, configuration: &Settings) -> Result<Server, std::io::Error> {
     if let Err(e) = create_workspaces_directory(&configuration) {
         return Err(e);
     };
 
-    let workspaces_path = configuration.workspaces_path.clone();
-    let server = HttpServer::new(move || App::new().configure(config_app(workspaces_path.clone())))
+    spawn_trash_purge(configuration);
+
+    let settings = configuration.clone();
+    let server = HttpServer::new(move || App::new().configure(config_app(settings.clone())))
         .listen(listener)?
         .run();
     Ok(server)
@@ -32,16 +44,125 @@ fn create_workspaces_directory(configuration: &Settings) -> Result<(), std::io::
     return Ok(());
 }
 
-fn config_app(workspace_path: String) -> Box<dyn Fn(&mut ServiceConfig)> {
+fn spawn_trash_purge(configuration: &Settings) {
+    let trash_directory = get_trash_directory(&configuration.workspaces_path);
+    let retention = get_retention(configuration);
+    let period = Duration::from_secs(configuration.trash_purge_interval_seconds.max(1));
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            // Removing large workspaces blocks, so the purge runs outside of the worker thread.
+            let trash_directory = trash_directory.clone();
+            let purge = tokio::task::spawn_blocking(move || {
+                purge_expired_workspaces(&trash_directory, retention)
+            });
+            if let Err(e) = purge.await {
+                eprintln!("Error while purging the trash: {:#?}", e);
+            }
+        }
+    });
+}
+
+fn config_app(configuration: Settings) -> Box<dyn Fn(&mut ServiceConfig)> {
     Box::new(move |cfg: &mut ServiceConfig| {
-        cfg.app_data(web::Data::new(workspace_path.clone()))
+        cfg.app_data(web::Data::new(configuration.workspaces_path.clone()))
+            .app_data(web::Data::new(configuration.clone()))
             .service(web::resource("/api/health_check").route(web::get().to(health_check)))
             .service(web::resource("/api/workspaces").route(web::get().to(retrieve_workspaces)))
+            .service(web::resource("/api/trash").route(web::get().to(retrieve_trash)))
+            .service(
+                web::resource("/api/trash/{trash_id}").route(web::delete().to(purge_workspace)),
+            )
+            .service(
+                web::resource("/api/trash/{trash_id}/restore")
+                    .route(web::post().to(restore_workspace)),
+            )
             .service(
                 web::resource("/api/workspaces/{workspace_name}")
                     .route(web::post().to(create_workspace))
+                    .route(web::put().to(rename_workspace))
+                    .route(web::patch().to(update_workspace))
                     .route(web::delete().to(delete_workspace)),
             )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/export")
+                    .route(web::get().to(export_workspace)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/import")
+                    .route(web::post().to(import_workspace)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/fork")
+                    .route(web::post().to(fork_workspace)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/usage")
+                    .route(web::get().to(retrieve_workspace_usage)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/merges")
+                    .route(web::get().to(retrieve_merge_sessions)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/merges/{session_id}")
+                    .route(web::get().to(retrieve_merge_session))
+                    .route(web::delete().to(abort_merge_session)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/merges/{session_id}/commit")
+                    .route(web::post().to(commit_merge_session)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/merges/{session_id}/files")
+                    .route(web::get().to(retrieve_conflict_file))
+                    .route(web::put().to(resolve_conflict_file))
+                    .route(web::delete().to(resolve_conflict_file_as_deleted)),
+            )
+            .service(
+                web::resource(
+                    "/api/workspaces/{workspace_name}/merges/{session_id}/files/{file_name}",
+                )
+                .route(web::get().to(retrieve_conflict_file))
+                .route(web::put().to(resolve_conflict_file))
+                .route(web::delete().to(resolve_conflict_file_as_deleted)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/rebase")
+                    .route(web::get().to(retrieve_rebase)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/rebase/continue")
+                    .route(web::post().to(continue_rebase)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/rebase/abort")
+                    .route(web::post().to(abort_rebase)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/rebase/files")
+                    .route(web::put().to(resolve_rebase_file))
+                    .route(web::delete().to(resolve_rebase_file_as_deleted)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/rebase/files/{file_name}")
+                    .route(web::put().to(resolve_rebase_file))
+                    .route(web::delete().to(resolve_rebase_file_as_deleted)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/tags")
+                    .route(web::get().to(retrieve_tags)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/tags/{tag_name}")
+                    .route(web::post().to(create_tag))
+                    .route(web::delete().to(delete_tag)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/compare")
+                    .route(web::get().to(compare_branches)),
+            )
             .service(
                 web::resource("/api/workspaces/{workspace_name}/branches")
                     .route(web::get().to(retrieve_branches)),
@@ -49,7 +170,53 @@ fn config_app(workspace_path: String) -> Box<dyn Fn(&mut ServiceConfig)> {
             .service(
                 web::resource("/api/workspaces/{workspace_name}/branches/{branch_name}")
                     .route(web::post().to(create_branches))
-                    .route(web::put().to(set_current_branch)),
+                    .route(web::put().to(set_current_branch))
+                    .route(web::delete().to(delete_branch)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/branches/{branch_name}/rename")
+                    .route(web::post().to(rename_branch)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/branches/{branch_name}/protection")
+                    .route(web::get().to(retrieve_branch_protection))
+                    .route(web::put().to(update_branch_protection)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/branches/{branch_name}/merge")
+                    .route(web::post().to(merge_branch)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/branches/{branch_name}/reset")
+                    .route(web::post().to(reset_branch)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/branches/{branch_name}/backups")
+                    .route(web::get().to(retrieve_branch_backups)),
+            )
+            .service(
+                web::resource(
+                    "/api/workspaces/{workspace_name}/branches/{branch_name}/backups/{backup_id}/restore",
+                )
+                .route(web::post().to(restore_branch_backup)),
+            )
+            .service(
+                web::resource(
+                    "/api/workspaces/{workspace_name}/branches/{branch_name}/cherry-pick",
+                )
+                .route(web::post().to(cherry_pick_commits)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/branches/{branch_name}/rebase")
+                    .route(web::post().to(rebase_branch)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/branches/{branch_name}/squash")
+                    .route(web::post().to(squash_commits)),
+            )
+            .service(
+                web::resource("/api/workspaces/{workspace_name}/branches/{branch_name}/revert")
+                    .route(web::post().to(revert_commit)),
             )
             .service(
                 web::resource("/api/workspaces/{workspace_name}/branches/{branch_name}/logs")
@@ -61,8 +228,9 @@ fn config_app(workspace_path: String) -> Box<dyn Fn(&mut ServiceConfig)> {
             )
             .service(
                 web::resource(
-                    "/api/workspaces/{workspace_name}/branches/{branch_name}/files/{file_name}",
+                    "/api/workspaces/{workspace_name}/branches/{branch_name}/files/{file_name:.+}",
                 )
+                .app_data(get_upload_form_config(&configuration.quotas))
                 .route(web::get().to(retrieve_file_content))
                 .route(web::post().to(create_file))
                 .route(web::put().to(update_file))
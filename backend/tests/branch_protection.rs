@@ -0,0 +1,407 @@
+use docversions::configuration::{get_configuration, Settings};
+use docversions::startup::run;
+use git2::{BranchType, Commit, ObjectType, Oid, Repository, Signature};
+use reqwest::multipart::{Form, Part};
+use std::collections::HashMap;
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn retrieve_branch_protection_returns_no_rules_for_new_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/api/workspaces/{}/branches/master/protection",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let status = response.status();
+    let protection: BranchProtection = response.json().await.expect("failed to get payload");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert!(!protection.no_direct_commits);
+    assert!(!protection.no_delete_or_reset);
+}
+
+#[tokio::test]
+async fn update_branch_protection_returns_404_on_non_existing_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = protect_branch(&client, &address, &workspace_name, "draft").await;
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn file_changes_on_protected_branch_return_403() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    create_branch_commit(&repository, "master", "README.md", "master\n").unwrap_or_else(|e| {
+        panic!("Error while creating the commit: {:?}", e);
+    });
+    let client = reqwest::Client::new();
+    let protect_response = protect_branch(&client, &address, &workspace_name, "master").await;
+    let master_tip = get_branch_tip(&repository, "master");
+
+    let create_response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/master/files/NEW.md",
+            &address, &workspace_name
+        ))
+        .multipart(create_multipart_form("new\n"))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let update_response = client
+        .put(format!(
+            "{}/api/workspaces/{}/branches/master/files/README.md",
+            &address, &workspace_name
+        ))
+        .multipart(create_multipart_form("updated\n"))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let delete_response = client
+        .delete(format!(
+            "{}/api/workspaces/{}/branches/master/files/README.md",
+            &address, &workspace_name
+        ))
+        .multipart(Form::new().text("commit_message", "commit message"))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let create_status = create_response.status().as_u16();
+    let create_reason = create_response.text().await.unwrap_or_default();
+
+    let new_master_tip = get_branch_tip(&repository, "master");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(protect_response.status().is_success());
+    assert_eq!(create_status, 403);
+    assert!(create_reason.contains("master"));
+    assert_eq!(update_response.status().as_u16(), 403);
+    assert_eq!(delete_response.status().as_u16(), 403);
+    assert_eq!(new_master_tip, master_tip);
+}
+
+#[tokio::test]
+async fn file_changes_on_protected_branch_through_other_names_are_refused() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+    let protect_response = protect_branch(&client, &address, &workspace_name, "master").await;
+    let master_tip = get_branch_tip(&repository, "master");
+    let short_commit_id = master_tip.unwrap().to_string()[..7].to_string();
+
+    let mut statuses = Vec::new();
+    for branch_name in ["HEAD", short_commit_id.as_str(), "missing"] {
+        let response = client
+            .post(format!(
+                "{}/api/workspaces/{}/branches/{}/files/a.txt",
+                &address, &workspace_name, branch_name
+            ))
+            .multipart(create_multipart_form("new\n"))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+        statuses.push(response.status().as_u16());
+    }
+
+    let new_master_tip = get_branch_tip(&repository, "master");
+    let head_detached = repository.head_detached().unwrap();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(protect_response.status().is_success());
+    assert_eq!(statuses, vec![400, 404, 404]);
+    assert_eq!(new_master_tip, master_tip);
+    assert!(!head_detached);
+}
+
+#[tokio::test]
+async fn merge_into_protected_branch_is_allowed() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    assert!(create_branch(&repository, "draft").is_ok());
+    let draft_tip = create_branch_commit(&repository, "draft", "README.md", "draft\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+    let protect_response = protect_branch(&client, &address, &workspace_name, "master").await;
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/master/merge",
+            &address, &workspace_name
+        ))
+        .json(&HashMap::from([("source", "draft")]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let master_tip = get_branch_tip(&repository, "master");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(protect_response.status().is_success());
+    assert!(response.status().is_success());
+    assert_eq!(master_tip, Some(draft_tip));
+}
+
+#[tokio::test]
+async fn delete_or_rename_protected_branch_returns_403() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    assert!(create_branch(&repository, "draft").is_ok());
+    let client = reqwest::Client::new();
+    let protect_response = protect_branch(&client, &address, &workspace_name, "draft").await;
+
+    let delete_response = client
+        .delete(format!(
+            "{}/api/workspaces/{}/branches/draft?force=true",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let rename_response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/draft/rename",
+            &address, &workspace_name
+        ))
+        .json(&HashMap::from([("name", "final")]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let draft_exists = repository.find_branch("draft", BranchType::Local).is_ok();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(protect_response.status().is_success());
+    assert_eq!(delete_response.status().as_u16(), 403);
+    assert_eq!(rename_response.status().as_u16(), 403);
+    assert!(draft_exists);
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct BranchProtection {
+    no_direct_commits: bool,
+    no_delete_or_reset: bool,
+}
+
+fn get_workspace_name() -> String {
+    Uuid::new_v4().to_simple_string()
+}
+
+fn configure_test(workspace_name: &str) -> std::io::Result<PathBuf> {
+    let mut configuration_file = PathBuf::from("tests_execution");
+    configuration_file.push(workspace_name);
+    configuration_file.set_extension("yaml");
+    std::fs::copy("configuration_test.yaml", &configuration_file)?;
+    Ok(configuration_file)
+}
+
+fn cleanup(configuration_file: &Path, configuration: &Settings, workspace_name: &str) {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace.as_path());
+    }
+    if configuration_file.exists() {
+        _ = fs::remove_file(configuration_file);
+    }
+}
+
+fn spawn_app(configuration: &Settings, workspace: &Path) -> String {
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace);
+    }
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let server = run(listener, configuration).expect("Failed to bind address");
+    tokio::spawn(server);
+    format!("http://127.0.0.1:{}", port)
+}
+
+fn get_workspace(configuration: &Settings, workspace_name: &str) -> PathBuf {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    workspace
+}
+
+fn create_git_repository(workspace: &Path) -> Result<Repository, git2::Error> {
+    Repository::init(workspace)
+}
+
+fn create_master_branch(repository: &Repository) -> Result<(), git2::Error> {
+    create_first_commit(repository)?;
+    let (object, reference) = repository.revparse_ext("master")?;
+    match reference {
+        Some(gref) => repository.set_head(gref.name().unwrap())?,
+        None => repository.set_head_detached(object.id())?,
+    };
+    Ok(())
+}
+
+fn create_first_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
+    let mut index = repository.index()?;
+    let oid = index.write_tree()?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+
+    let tree = repository.find_tree(oid)?;
+
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "commit message",
+        &tree,
+        &[],
+    )
+}
+
+fn find_last_commit(repo: &Repository) -> Result<Commit<'_>, git2::Error> {
+    let obj = repo.head()?.resolve()?.peel(ObjectType::Commit)?;
+    obj.into_commit()
+        .map_err(|_| git2::Error::from_str("Couldn't find commit"))
+}
+
+fn create_branch(repository: &Repository, branch_name: &str) -> Result<(), git2::Error> {
+    let last_commit = find_last_commit(repository)?;
+    repository.branch(branch_name, &last_commit, false)?;
+    Ok(())
+}
+
+fn create_branch_commit(
+    repository: &Repository,
+    branch_name: &str,
+    file_name: &str,
+    content: &str,
+) -> Result<Oid, git2::Error> {
+    let parent = repository
+        .find_branch(branch_name, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let blob = repository.blob(content.as_bytes())?;
+    let mut tree_builder = repository.treebuilder(Some(&parent.tree()?))?;
+    tree_builder.insert(file_name, blob, 0o100644)?;
+    let tree = repository.find_tree(tree_builder.write()?)?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    repository.commit(
+        Some(&format!("refs/heads/{}", branch_name)),
+        &signature,
+        &signature,
+        &format!("update {}", file_name),
+        &tree,
+        &[&parent],
+    )
+}
+
+fn get_branch_tip(repository: &Repository, branch_name: &str) -> Option<Oid> {
+    repository
+        .find_branch(branch_name, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .map(|c| c.id())
+        .ok()
+}
+
+async fn protect_branch(
+    client: &reqwest::Client,
+    address: &str,
+    workspace_name: &str,
+    branch_name: &str,
+) -> reqwest::Response {
+    client
+        .put(format!(
+            "{}/api/workspaces/{}/branches/{}/protection",
+            address, workspace_name, branch_name
+        ))
+        .json(&HashMap::from([
+            ("no_direct_commits", true),
+            ("no_delete_or_reset", true),
+        ]))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+fn create_multipart_form(content: &str) -> Form {
+    Form::new()
+        .part(
+            "file",
+            Part::bytes(content.as_bytes().to_vec()).file_name("README.md"),
+        )
+        .text("commit_message", "commit message")
+}
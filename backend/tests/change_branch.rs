@@ -134,6 +134,105 @@ async fn change_branch_to_existent_branch_returns_200() {
     assert!(response.status().is_success());
 }
 
+#[tokio::test]
+async fn change_branch_returns_400_on_revspec_branch_name() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .put(format!(
+            "{}/api/workspaces/{}/branches/master~1",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn change_branch_returns_400_on_names_ending_in_head() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let mut statuses = Vec::new();
+    for branch_name in ["HEAD", "ORIG_HEAD"] {
+        let response = client
+            .put(format!(
+                "{}/api/workspaces/{}/branches/{}",
+                &address, &workspace_name, branch_name
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+        statuses.push(response.status().as_u16());
+    }
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(statuses, vec![400, 400]);
+}
+
+#[tokio::test]
+async fn change_branch_to_commit_id_does_not_detach_head() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let last_commit = find_last_commit(&repository).unwrap().id().to_string();
+    let client = reqwest::Client::new();
+
+    let response = client
+        .put(format!(
+            "{}/api/workspaces/{}/branches/{}",
+            &address,
+            &workspace_name,
+            &last_commit[..7]
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let head_detached = repository.head_detached().unwrap();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(!response.status().is_success());
+    assert!(!head_detached);
+}
+
 fn get_workspace_name() -> String {
     let uuid = Uuid::new_v4().to_simple_string();
     return uuid;
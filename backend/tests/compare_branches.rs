@@ -0,0 +1,352 @@
+use docversions::configuration::{get_configuration, Settings};
+use docversions::startup::run;
+use git2::{BranchType, Commit, ObjectType, Oid, Repository, Signature};
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn compare_branches_returns_commits_and_changed_files() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let merge_base = create_branch_commit(&repository, "master", "README.md", "first line\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    assert!(create_branch(&repository, "draft").is_ok());
+    let first_draft_commit = create_branch_commit(
+        &repository,
+        "draft",
+        "README.md",
+        "first line\nsecond line\n",
+    )
+    .unwrap_or_else(|e| {
+        panic!("Error while creating the commit: {:?}", e);
+    });
+    let second_draft_commit = create_branch_commit(&repository, "draft", "DRAFT.md", "draft\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let master_commit = create_branch_commit(&repository, "master", "MASTER.md", "master\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/api/workspaces/{}/compare?base=master&head=draft",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let status = response.status();
+    let comparison: BranchComparison = response.json().await.expect("failed to get payload");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert_eq!(comparison.merge_base, Some(merge_base.to_string()));
+    assert_eq!(comparison.ahead_by, 2);
+    assert_eq!(comparison.behind_by, 1);
+    let ahead_commits: Vec<String> = comparison
+        .ahead_commits
+        .into_iter()
+        .map(|c| c.commit_uuid)
+        .collect();
+    assert!(ahead_commits.contains(&first_draft_commit.to_string()));
+    assert!(ahead_commits.contains(&second_draft_commit.to_string()));
+    assert_eq!(
+        comparison.behind_commits[0].commit_uuid,
+        master_commit.to_string()
+    );
+    assert_eq!(comparison.files.len(), 2);
+    let draft_file = comparison
+        .files
+        .iter()
+        .find(|f| f.name == "DRAFT.md")
+        .expect("DRAFT.md not found");
+    assert_eq!(draft_file.status, "added");
+    assert_eq!(draft_file.additions, 1);
+    assert_eq!(draft_file.deletions, 0);
+    let readme_file = comparison
+        .files
+        .iter()
+        .find(|f| f.name == "README.md")
+        .expect("README.md not found");
+    assert_eq!(readme_file.status, "modified");
+    assert_eq!(readme_file.additions, 1);
+    assert_eq!(readme_file.deletions, 0);
+}
+
+#[tokio::test]
+async fn compare_branches_returns_deleted_files() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    create_branch_commit(&repository, "master", "README.md", "first line\n").unwrap_or_else(|e| {
+        panic!("Error while creating the commit: {:?}", e);
+    });
+    assert!(create_branch(&repository, "draft").is_ok());
+    delete_branch_file(&repository, "draft", "README.md").unwrap_or_else(|e| {
+        panic!("Error while creating the commit: {:?}", e);
+    });
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/api/workspaces/{}/compare?base=master&head=draft",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let status = response.status();
+    let comparison: BranchComparison = response.json().await.expect("failed to get payload");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert_eq!(comparison.ahead_by, 1);
+    assert_eq!(comparison.behind_by, 0);
+    assert_eq!(comparison.files.len(), 1);
+    assert_eq!(comparison.files[0].name, "README.md");
+    assert_eq!(comparison.files[0].status, "deleted");
+    assert_eq!(comparison.files[0].additions, 0);
+    assert_eq!(comparison.files[0].deletions, 1);
+}
+
+#[tokio::test]
+async fn compare_branches_returns_404_on_non_existing_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/api/workspaces/{}/compare?base=master&head=draft",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn compare_branches_returns_400_on_invalid_branch_name() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/api/workspaces/{}/compare?base=master&head=-draft",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct BranchComparison {
+    merge_base: Option<String>,
+    ahead_by: usize,
+    behind_by: usize,
+    ahead_commits: Vec<GitLog>,
+    behind_commits: Vec<GitLog>,
+    files: Vec<ChangedFile>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLog {
+    commit_uuid: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ChangedFile {
+    name: String,
+    status: String,
+    additions: usize,
+    deletions: usize,
+}
+
+fn get_workspace_name() -> String {
+    Uuid::new_v4().to_simple_string()
+}
+
+fn configure_test(workspace_name: &str) -> std::io::Result<PathBuf> {
+    let mut configuration_file = PathBuf::from("tests_execution");
+    configuration_file.push(workspace_name);
+    configuration_file.set_extension("yaml");
+    std::fs::copy("configuration_test.yaml", &configuration_file)?;
+    Ok(configuration_file)
+}
+
+fn cleanup(configuration_file: &Path, configuration: &Settings, workspace_name: &str) {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace.as_path());
+    }
+    if configuration_file.exists() {
+        _ = fs::remove_file(configuration_file);
+    }
+}
+
+fn spawn_app(configuration: &Settings, workspace: &Path) -> String {
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace);
+    }
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let server = run(listener, configuration).expect("Failed to bind address");
+    tokio::spawn(server);
+    format!("http://127.0.0.1:{}", port)
+}
+
+fn get_workspace(configuration: &Settings, workspace_name: &str) -> PathBuf {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    workspace
+}
+
+fn create_git_repository(workspace: &Path) -> Result<Repository, git2::Error> {
+    Repository::init(workspace)
+}
+
+fn create_master_branch(repository: &Repository) -> Result<(), git2::Error> {
+    create_first_commit(repository)?;
+    let (object, reference) = repository.revparse_ext("master")?;
+    match reference {
+        Some(gref) => repository.set_head(gref.name().unwrap())?,
+        None => repository.set_head_detached(object.id())?,
+    };
+    Ok(())
+}
+
+fn create_first_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
+    let mut index = repository.index()?;
+    let oid = index.write_tree()?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+
+    let tree = repository.find_tree(oid)?;
+
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "commit message",
+        &tree,
+        &[],
+    )
+}
+
+fn find_last_commit(repo: &Repository) -> Result<Commit<'_>, git2::Error> {
+    let obj = repo.head()?.resolve()?.peel(ObjectType::Commit)?;
+    obj.into_commit()
+        .map_err(|_| git2::Error::from_str("Couldn't find commit"))
+}
+
+fn create_branch(repository: &Repository, branch_name: &str) -> Result<(), git2::Error> {
+    let last_commit = find_last_commit(repository)?;
+    repository.branch(branch_name, &last_commit, false)?;
+    Ok(())
+}
+
+fn create_branch_commit(
+    repository: &Repository,
+    branch_name: &str,
+    file_name: &str,
+    content: &str,
+) -> Result<Oid, git2::Error> {
+    let parent = repository
+        .find_branch(branch_name, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let blob = repository.blob(content.as_bytes())?;
+    let mut tree_builder = repository.treebuilder(Some(&parent.tree()?))?;
+    tree_builder.insert(file_name, blob, 0o100644)?;
+    let tree = repository.find_tree(tree_builder.write()?)?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    repository.commit(
+        Some(&format!("refs/heads/{}", branch_name)),
+        &signature,
+        &signature,
+        &format!("update {}", file_name),
+        &tree,
+        &[&parent],
+    )
+}
+
+fn delete_branch_file(
+    repository: &Repository,
+    branch_name: &str,
+    file_name: &str,
+) -> Result<Oid, git2::Error> {
+    let parent = repository
+        .find_branch(branch_name, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let mut tree_builder = repository.treebuilder(Some(&parent.tree()?))?;
+    tree_builder.remove(file_name)?;
+    let tree = repository.find_tree(tree_builder.write()?)?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    repository.commit(
+        Some(&format!("refs/heads/{}", branch_name)),
+        &signature,
+        &signature,
+        &format!("delete {}", file_name),
+        &tree,
+        &[&parent],
+    )
+}
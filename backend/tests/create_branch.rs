@@ -1,9 +1,10 @@
 use docversions::configuration::{get_configuration, Settings};
 use docversions::startup::run;
-use git2::{Commit, ObjectType, Repository, Signature};
+use git2::{BranchType, Commit, ObjectType, Oid, Repository, Signature};
+use std::collections::HashMap;
 use std::fs;
 use std::net::TcpListener;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 #[tokio::test]
@@ -102,6 +103,251 @@ async fn create_branch_sets_head_to_last_commit_for_repository_with_master_branc
     cleanup(&configuration_file, &configuration, &workspace_name);
 }
 
+#[tokio::test]
+async fn create_branch_starts_from_default_branch_instead_of_head() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let master_tip = find_last_commit(&repository).map(|c| c.id());
+    let last_commit = find_last_commit(&repository).unwrap();
+    assert!(repository.branch("draft", &last_commit, false).is_ok());
+    assert!(create_branch_commit(&repository, "draft", "DRAFT.md", "draft\n").is_ok());
+    assert!(repository.set_head("refs/heads/draft").is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/new_branch",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let new_branch_tip = get_branch_tip(&repository, "new_branch");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(response.status().is_success());
+    assert_eq!(new_branch_tip, master_tip.ok());
+}
+
+#[tokio::test]
+async fn create_branch_starts_from_tag_or_commit_id() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let first_commit = find_last_commit(&repository).unwrap();
+    assert!(repository
+        .tag_lightweight("v1", first_commit.as_object(), false)
+        .is_ok());
+    let second_commit = create_branch_commit(&repository, "master", "README.md", "second\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    assert!(create_branch_commit(&repository, "master", "README.md", "third\n").is_ok());
+    let client = reqwest::Client::new();
+
+    let tag_response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/from_tag",
+            &address, &workspace_name
+        ))
+        .json(&HashMap::from([("start_point", "v1")]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let commit_response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/from_commit",
+            &address, &workspace_name
+        ))
+        .json(&HashMap::from([("start_point", second_commit.to_string())]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let from_tag_tip = get_branch_tip(&repository, "from_tag");
+    let from_commit_tip = get_branch_tip(&repository, "from_commit");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(tag_response.status().is_success());
+    assert!(commit_response.status().is_success());
+    assert_eq!(from_tag_tip, Some(first_commit.id()));
+    assert_eq!(from_commit_tip, Some(second_commit));
+}
+
+#[tokio::test]
+async fn create_branch_returns_404_on_unknown_start_point() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/new_branch",
+            &address, &workspace_name
+        ))
+        .json(&HashMap::from([("start_point", "master~1")]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let branch_exists = get_branch_tip(&repository, "new_branch").is_some();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 404);
+    assert!(!branch_exists);
+}
+
+#[tokio::test]
+async fn create_branch_returns_400_on_invalid_body() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let mut statuses = Vec::new();
+    for body in [
+        "{\"start_point\": 5}",
+        "{\"start_piont\": \"master\"}",
+        "start_point=master",
+    ] {
+        let response = client
+            .post(format!(
+                "{}/api/workspaces/{}/branches/new_branch",
+                &address, &workspace_name
+            ))
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .expect("Failed to execute request.");
+        statuses.push(response.status().as_u16());
+    }
+
+    let branch_exists = get_branch_tip(&repository, "new_branch").is_some();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(statuses, vec![400, 400, 400]);
+    assert!(!branch_exists);
+}
+
+#[tokio::test]
+async fn create_branch_checks_out_the_start_point() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let first_commit = find_last_commit(&repository).unwrap();
+    let head_commit_result = create_head_commit(&repository, &workspace, "NEW.md", "new\n");
+    assert!(head_commit_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/new_branch",
+            &address, &workspace_name
+        ))
+        .json(&HashMap::from([(
+            "start_point",
+            first_commit.id().to_string(),
+        )]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let head = repository
+        .head()
+        .ok()
+        .and_then(|h| h.name().map(String::from));
+    let index_tree = repository.index().and_then(|mut index| {
+        index.read(true)?;
+        index.write_tree()
+    });
+    let new_file_exists = workspace.join("NEW.md").exists();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(response.status().is_success());
+    assert_eq!(head, Some("refs/heads/new_branch".to_string()));
+    assert_eq!(index_tree.ok(), Some(first_commit.tree_id()));
+    assert!(!new_file_exists);
+}
+
+#[tokio::test]
+async fn create_branch_returns_409_on_already_existing_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/master",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 409);
+}
+
 fn get_workspace_name() -> String {
     let uuid = Uuid::new_v4().to_simple_string();
     return uuid;
@@ -179,3 +425,60 @@ fn find_last_commit(repo: &Repository) -> Result<Commit, git2::Error> {
     obj.into_commit()
         .map_err(|_| git2::Error::from_str("Couldn't find commit"))
 }
+
+fn create_branch_commit(
+    repository: &Repository,
+    branch_name: &str,
+    file_name: &str,
+    content: &str,
+) -> Result<Oid, git2::Error> {
+    let parent = repository
+        .find_branch(branch_name, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let blob = repository.blob(content.as_bytes())?;
+    let mut tree_builder = repository.treebuilder(Some(&parent.tree()?))?;
+    tree_builder.insert(file_name, blob, 0o100644)?;
+    let tree = repository.find_tree(tree_builder.write()?)?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    repository.commit(
+        Some(&format!("refs/heads/{}", branch_name)),
+        &signature,
+        &signature,
+        &format!("update {}", file_name),
+        &tree,
+        &[&parent],
+    )
+}
+
+fn create_head_commit(
+    repository: &Repository,
+    workspace: &Path,
+    file_name: &str,
+    content: &str,
+) -> Result<Oid, git2::Error> {
+    fs::write(workspace.join(file_name), content)
+        .map_err(|e| git2::Error::from_str(&e.to_string()))?;
+    let mut index = repository.index()?;
+    index.add_path(Path::new(file_name))?;
+    index.write()?;
+    let tree = repository.find_tree(index.write_tree()?)?;
+    let parent = find_last_commit(repository)?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        &format!("add {}", file_name),
+        &tree,
+        &[&parent],
+    )
+}
+
+fn get_branch_tip(repository: &Repository, branch_name: &str) -> Option<Oid> {
+    repository
+        .find_branch(branch_name, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .map(|c| c.id())
+        .ok()
+}
@@ -15,7 +15,7 @@ const ULIMIT_OPEN_FILES_SOFT: u64 = 16384;
 const ULIMIT_OPEN_FILES_HARD: u64 = ULIMIT_OPEN_FILES_SOFT * 2;
 
 #[tokio::test]
-async fn create_file_returns_500_for_initialized_repository() {
+async fn create_file_returns_404_for_initialized_repository() {
     let workspace_name = get_workspace_name();
     let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
         panic!("Error while configuring the test: {:?}.", error);
@@ -49,7 +49,7 @@ async fn create_file_returns_500_for_initialized_repository() {
         .expect("Failed to execute request.");
 
     cleanup(&configuration_file, &configuration, &workspace_name);
-    assert!(response.status().is_server_error());
+    assert_eq!(response.status().as_u16(), 404);
 }
 
 #[tokio::test]
@@ -230,6 +230,194 @@ async fn create_file_returns_409_for_repository_with_master_branch_and_already_c
     assert!(response.status().is_client_error());
 }
 
+#[tokio::test]
+async fn create_file_returns_413_when_file_exceeds_max_file_size() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_quota_test(&workspace_name, "  max_file_size: 10")
+        .unwrap_or_else(|error| {
+            panic!("Error while configuring the test: {:?}.", error);
+        });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+    let branch_name = "master".to_string();
+    let file_name = "README.md".to_string();
+    let commit_message = "upload file".to_string();
+    let mut file_path = PathBuf::from("tests_execution");
+    file_path.push(format!("{}_README.md", &workspace_name));
+    let copy_file_result = copy_test_file(&file_path);
+    assert!(copy_file_result.is_ok());
+    let multipart_form = create_multipart_form(&file_path, &file_name, &commit_message)
+        .await
+        .expect("Failed to create the multipart form.");
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/{}/files/{}",
+            &address, &workspace_name, &branch_name, &file_name
+        ))
+        .multipart(multipart_form)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let last_commit_message =
+        find_last_commit(&repository).map(|c| c.message().map(|m| m.to_string()));
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 413);
+    assert_ne!(last_commit_message.ok().flatten(), Some(commit_message));
+}
+
+#[tokio::test]
+async fn create_file_returns_413_when_upload_exceeds_max_file_size_while_streaming() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_quota_test(&workspace_name, "  max_file_size: 10")
+        .unwrap_or_else(|error| {
+            panic!("Error while configuring the test: {:?}.", error);
+        });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+    let branch_name = "master".to_string();
+    let file_name = "README.md".to_string();
+    let commit_message = "upload file".to_string();
+    let mut file_path = PathBuf::from("tests_execution");
+    file_path.push(format!("{}_README.md", &workspace_name));
+    let write_file_result = fs::write(&file_path, vec![b'a'; 1024 * 1024]);
+    assert!(write_file_result.is_ok());
+    let multipart_form = create_multipart_form(&file_path, &file_name, &commit_message)
+        .await
+        .expect("Failed to create the multipart form.");
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/{}/files/{}",
+            &address, &workspace_name, &branch_name, &file_name
+        ))
+        .multipart(multipart_form)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let status = response.status().as_u16();
+    let body = response.text().await.unwrap_or_default();
+    let last_commit_message =
+        find_last_commit(&repository).map(|c| c.message().map(|m| m.to_string()));
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(status, 413);
+    assert_eq!(body, "The file exceeds the maximum file size of 10 bytes.");
+    assert_ne!(last_commit_message.ok().flatten(), Some(commit_message));
+}
+
+#[tokio::test]
+async fn create_file_returns_507_when_workspace_exceeds_max_files() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_quota_test(&workspace_name, "  max_files: 1")
+        .unwrap_or_else(|error| {
+            panic!("Error while configuring the test: {:?}.", error);
+        });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let copy_file_result = copy_file(&configuration, &workspace_name);
+    assert!(copy_file_result.is_ok());
+    let second_commit_result = create_second_commit(&repository);
+    assert!(second_commit_result.is_ok());
+    let client = reqwest::Client::new();
+    let branch_name = "master".to_string();
+    let file_name = "NOTES.md".to_string();
+    let commit_message = "upload file".to_string();
+    let mut file_path = PathBuf::from("tests_execution");
+    file_path.push(format!("{}_README.md", &workspace_name));
+    let copy_file_result = copy_test_file(&file_path);
+    assert!(copy_file_result.is_ok());
+    let multipart_form = create_multipart_form(&file_path, &file_name, &commit_message)
+        .await
+        .expect("Failed to create the multipart form.");
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/{}/files/{}",
+            &address, &workspace_name, &branch_name, &file_name
+        ))
+        .multipart(multipart_form)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let last_commit_message =
+        find_last_commit(&repository).map(|c| c.message().map(|m| m.to_string()));
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 507);
+    assert_ne!(last_commit_message.ok().flatten(), Some(commit_message));
+}
+
+#[tokio::test]
+async fn create_file_on_commit_id_does_not_commit_on_detached_head() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let master_tip = find_last_commit(&repository).unwrap().id();
+    let client = reqwest::Client::new();
+    let branch_name = master_tip.to_string()[..7].to_string();
+    let file_name = "h.txt".to_string();
+    let commit_message = "create file".to_string();
+    let mut file_path = PathBuf::from("tests_execution");
+    file_path.push(format!("{}_README.md", &workspace_name));
+    let copy_file_result = copy_test_file(&file_path);
+    assert!(copy_file_result.is_ok());
+    let multipart_form = create_multipart_form(&file_path, &file_name, &commit_message)
+        .await
+        .expect("Failed to create the multipart form.");
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/{}/files/{}",
+            &address, &workspace_name, &branch_name, &file_name
+        ))
+        .multipart(multipart_form)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let head_detached = repository.head_detached().unwrap();
+    let last_commit = find_last_commit(&repository).unwrap().id();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(!response.status().is_success());
+    assert!(!head_detached);
+    assert_eq!(last_commit, master_tip);
+}
+
 fn get_workspace_name() -> String {
     let uuid = Uuid::new_v4().to_simple_string();
     return uuid;
@@ -249,6 +437,14 @@ fn configure_test(workspace_name: &String) -> std::io::Result<PathBuf> {
     Ok(configuration_file)
 }
 
+fn configure_quota_test(workspace_name: &String, quotas: &str) -> std::io::Result<PathBuf> {
+    let configuration_file = configure_test(workspace_name)?;
+    let mut configuration = fs::read_to_string(&configuration_file)?;
+    configuration.push_str(&format!("\nquotas:\n{}\n", quotas));
+    fs::write(&configuration_file, configuration)?;
+    Ok(configuration_file)
+}
+
 fn cleanup(configuration_file: &PathBuf, configuration: &Settings, workspace_name: &String) {
     let mut workspace = PathBuf::from(&configuration.workspaces_path);
     workspace.push(&workspace_name);
@@ -343,11 +539,11 @@ fn copy_file(configuration: &Settings, workspace_name: &String) -> std::io::Resu
     let mut file_path = PathBuf::from(&configuration.workspaces_path);
     file_path.push(workspace_name);
     file_path.push("README.md");
-    std::fs::copy("worspace_README.md", &file_path)
+    std::fs::copy("templates/default/README.md", &file_path)
 }
 
 fn copy_test_file(test_file_path: &PathBuf) -> std::io::Result<u64> {
-    std::fs::copy("worspace_README.md", &test_file_path)
+    std::fs::copy("templates/default/README.md", test_file_path)
 }
 
 fn create_second_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
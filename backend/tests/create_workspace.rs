@@ -1,8 +1,10 @@
 use docversions::configuration::{get_configuration, Settings};
 use docversions::startup::run;
 use git2::{Repository, Signature};
+use std::collections::HashMap;
 use std::fs;
 use std::net::TcpListener;
+use std::path::Path;
 use std::path::PathBuf;
 use uuid::Uuid;
 
@@ -55,6 +57,167 @@ async fn create_workspace_returns_409_on_already_existing_workspace() {
     assert!(response.status().is_client_error());
 }
 
+#[tokio::test]
+async fn create_workspace_commits_the_default_template_files() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let client = reqwest::Client::new();
+
+    client
+        .post(format!("{}/api/workspaces/{}", &address, &workspace_name))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let repository = Repository::open(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let last_commit = repository.head().unwrap().peel_to_commit().unwrap();
+    let summary = last_commit.summary().map(|s| s.to_string());
+    let readme = last_commit
+        .tree()
+        .unwrap()
+        .get_path(Path::new("README.md"))
+        .is_ok();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(summary, Some("Initial commit".to_string()));
+    assert_eq!(last_commit.parent_count(), 0);
+    assert!(readme);
+}
+
+#[tokio::test]
+async fn create_workspace_returns_400_on_non_existing_template() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/api/workspaces/{}", &address, &workspace_name))
+        .json(&HashMap::from([("template", "non_existent_template")]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let workspace_exists = workspace.exists();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 400);
+    assert!(!workspace_exists);
+}
+
+#[tokio::test]
+async fn create_workspace_returns_400_on_invalid_body() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let client = reqwest::Client::new();
+
+    let mut statuses = Vec::new();
+    for body in [
+        "{\"tempalte\": \"default\"}",
+        "{\"template\": 1}",
+        "template=default",
+    ] {
+        let response = client
+            .post(format!("{}/api/workspaces/{}", &address, &workspace_name))
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .expect("Failed to execute request.");
+        statuses.push(response.status().as_u16());
+    }
+
+    let workspace_exists = workspace.exists();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(statuses, vec![400, 400, 400]);
+    assert!(!workspace_exists);
+}
+
+#[tokio::test]
+async fn create_workspace_returns_500_on_broken_template() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    fs::write(
+        &configuration_file,
+        format!(
+            "application_port: 8000\nworkspaces_path: \"{}\"\ntemplates:\n  broken:\n    path: \"templates/non_existent_directory\"\n",
+            &configuration.workspaces_path
+        ),
+    )
+    .expect("Failed to update the configuration file.");
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/api/workspaces/{}", &address, &workspace_name))
+        .json(&HashMap::from([("template", "broken")]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let workspace_exists = workspace.exists();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 500);
+    assert!(!workspace_exists);
+}
+
+#[tokio::test]
+async fn create_workspace_returns_400_on_invalid_workspace_name() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let client = reqwest::Client::new();
+
+    let hidden_response = client
+        .post(format!("{}/api/workspaces/.trash", &address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let separator_response = client
+        .post(format!(
+            "{}/api/workspaces/{}%2Fnested",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let workspace_exists = workspace.exists();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(hidden_response.status().as_u16(), 400);
+    assert_eq!(separator_response.status().as_u16(), 400);
+    assert!(!workspace_exists);
+}
+
 fn get_workspace_name() -> String {
     let uuid = Uuid::new_v4().to_simple_string();
     return uuid;
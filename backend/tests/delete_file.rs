@@ -13,7 +13,7 @@ const ULIMIT_OPEN_FILES_SOFT: u64 = 16384;
 const ULIMIT_OPEN_FILES_HARD: u64 = ULIMIT_OPEN_FILES_SOFT * 2;
 
 #[tokio::test]
-async fn delete_file_returns_500_for_initialized_repository() {
+async fn delete_file_returns_404_for_initialized_repository() {
     let workspace_name = get_workspace_name();
     let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
         panic!("Error while configuring the test: {:?}.", error);
@@ -41,7 +41,7 @@ async fn delete_file_returns_500_for_initialized_repository() {
         .expect("Failed to execute request.");
 
     cleanup(&configuration_file, &configuration, &workspace_name);
-    assert!(response.status().is_server_error());
+    assert_eq!(response.status().as_u16(), 404);
 }
 
 #[tokio::test]
@@ -261,7 +261,7 @@ fn copy_file(configuration: &Settings, workspace_name: &String) -> std::io::Resu
     let mut file_path = PathBuf::from(&configuration.workspaces_path);
     file_path.push(workspace_name);
     file_path.push("README.md");
-    std::fs::copy("worspace_README.md", &file_path)
+    std::fs::copy("templates/default/README.md", &file_path)
 }
 
 fn create_second_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
@@ -55,6 +55,44 @@ async fn delete_workspace_returns_404_on_non_existing_workspace() {
     assert!(response.status().is_client_error());
 }
 
+#[tokio::test]
+async fn delete_workspace_moves_workspace_to_trash() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let _ = client
+        .delete(format!("{}/api/workspaces/{}", &address, &workspace_name))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let workspace_exists = workspace.exists();
+    let trashed_repository = fs::read_dir(get_trash_directory(&configuration))
+        .expect("Failed to read the trash directory.")
+        .filter_map(Result::ok)
+        .find(|e| {
+            e.file_name()
+                .to_string_lossy()
+                .ends_with(workspace_name.as_str())
+        })
+        .map(|e| Repository::open(e.path()).is_ok());
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(!workspace_exists);
+    assert_eq!(trashed_repository, Some(true));
+}
+
 fn get_workspace_name() -> String {
     let uuid = Uuid::new_v4().to_simple_string();
     return uuid;
@@ -77,6 +115,22 @@ fn cleanup(configuration_file: &PathBuf, configuration: &Settings, workspace_nam
     if configuration_file.exists() {
         _ = fs::remove_file(configuration_file.as_path());
     }
+    if let Ok(entries) = fs::read_dir(get_trash_directory(configuration)) {
+        entries
+            .filter_map(Result::ok)
+            .filter(|e| {
+                e.file_name()
+                    .to_string_lossy()
+                    .ends_with(workspace_name.as_str())
+            })
+            .for_each(|e| _ = fs::remove_dir_all(e.path()));
+    }
+}
+
+fn get_trash_directory(configuration: &Settings) -> PathBuf {
+    let mut trash_directory = PathBuf::from(&configuration.workspaces_path);
+    trash_directory.push(".trash");
+    trash_directory
 }
 
 fn spawn_app(configuration: &Settings, workspace: &PathBuf) -> String {
@@ -0,0 +1,429 @@
+use docversions::configuration::{get_configuration, Settings};
+use docversions::startup::run;
+use flate2::read::GzDecoder;
+use git2::{Commit, IndexAddOption, ObjectType, Repository, Signature};
+use std::fs;
+use std::io::Read;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn export_workspace_returns_404_on_non_existing_workspace() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/api/workspaces/{}/export",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn export_workspace_returns_git_bundle_with_all_references() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let last_commit = find_last_commit(&repository).unwrap_or_else(|e| {
+        panic!("Error while retrieving the last commit: {:?}", e);
+    });
+    assert!(repository.branch("new_branch", &last_commit, false).is_ok());
+    let last_commit_id = last_commit.id();
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/api/workspaces/{}/export?format=bundle",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let status = response.status();
+    let bundle = response.bytes().await.expect("failed to get payload");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    let bundle_content = String::from_utf8_lossy(&bundle);
+    assert!(bundle_content.starts_with("# v2 git bundle\n"));
+    assert!(bundle_content.contains(&format!("{} refs/heads/master\n", last_commit_id)));
+    assert!(bundle_content.contains(&format!("{} refs/heads/new_branch\n", last_commit_id)));
+    assert!(bundle_content.contains("\n\nPACK"));
+}
+
+#[tokio::test]
+async fn export_workspace_returns_tar_gz_with_branch_snapshot() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let copy_file_result = copy_file(&workspace);
+    assert!(copy_file_result.is_ok());
+    let second_commit_result = create_second_commit(&repository);
+    assert!(second_commit_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/api/workspaces/{}/export?format=tar.gz&branch=master",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let status = response.status();
+    let archive = response.bytes().await.expect("failed to get payload");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    let mut archive = tar::Archive::new(GzDecoder::new(archive.as_ref()));
+    let mut files = Vec::new();
+    for entry in archive.entries().expect("Failed to read the archive.") {
+        let mut entry = entry.expect("Failed to read the archive entry.");
+        let path = entry.path().unwrap().to_str().unwrap().to_string();
+        let mut content = String::new();
+        entry.read_to_string(&mut content).unwrap();
+        files.push((path, content));
+    }
+    let expected_content = fs::read_to_string("templates/default/README.md").unwrap();
+    assert_eq!(
+        files,
+        vec![(format!("{}/README.md", &workspace_name), expected_content)]
+    );
+}
+
+#[tokio::test]
+async fn export_workspace_streams_large_archives() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let large_content: Vec<u8> = (0..4 * 1024 * 1024u32)
+        .map(|i| (i.wrapping_mul(2654435761) >> 24) as u8)
+        .collect();
+    let large_commit_result = create_large_commit(&repository, &large_content);
+    assert!(large_commit_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/api/workspaces/{}/export?format=tar.gz&branch=master",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let status = response.status();
+    let content_length = response.content_length();
+    let archive = response.bytes().await.expect("failed to get payload");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert_eq!(content_length, None);
+    let mut archive = tar::Archive::new(GzDecoder::new(archive.as_ref()));
+    let mut large_file = None;
+    for entry in archive.entries().expect("Failed to read the archive.") {
+        let mut entry = entry.expect("Failed to read the archive entry.");
+        if entry.path().unwrap().ends_with("large.bin") {
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content).unwrap();
+            large_file = Some(content);
+        }
+    }
+    assert_eq!(large_file, Some(large_content));
+}
+
+#[tokio::test]
+async fn export_workspace_returns_tar_gz_of_default_branch_without_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let rename_result = repository
+        .find_branch("master", git2::BranchType::Local)
+        .and_then(|mut b| b.rename("main", false))
+        .and_then(|_| repository.set_head("refs/heads/main"))
+        .and_then(|_| repository.config())
+        .and_then(|mut c| c.set_str("docversions.defaultbranch", "main"));
+    assert!(rename_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/api/workspaces/{}/export?format=tar.gz",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let status = response.status();
+    let disposition = response
+        .headers()
+        .get("Content-Disposition")
+        .map(|h| h.to_str().unwrap().to_string());
+    let archive = response.bytes().await.expect("failed to get payload");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert_eq!(
+        disposition,
+        Some(format!(
+            "attachment; filename=\"{}-main.tar.gz\"",
+            &workspace_name
+        ))
+    );
+    let mut archive = tar::Archive::new(GzDecoder::new(archive.as_ref()));
+    let entries = archive
+        .entries()
+        .map(|entries| entries.filter_map(Result::ok).count());
+    assert!(entries.is_ok());
+}
+
+#[tokio::test]
+async fn export_workspace_returns_tar_gz_of_tag() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let copy_file_result = copy_file(&workspace);
+    assert!(copy_file_result.is_ok());
+    let second_commit_result = create_second_commit(&repository);
+    assert!(second_commit_result.is_ok());
+    let last_commit = find_last_commit(&repository).unwrap();
+    assert!(repository
+        .tag_lightweight("v1", last_commit.as_object(), false)
+        .is_ok());
+    let large_commit_result = create_large_commit(&repository, b"after the tag\n");
+    assert!(large_commit_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/api/workspaces/{}/export?format=tar.gz&branch=v1",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let status = response.status();
+    let archive = response.bytes().await.expect("failed to get payload");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    let mut archive = tar::Archive::new(GzDecoder::new(archive.as_ref()));
+    let files: Vec<String> = archive
+        .entries()
+        .expect("Failed to read the archive.")
+        .map(|entry| {
+            let entry = entry.expect("Failed to read the archive entry.");
+            entry.path().unwrap().to_str().unwrap().to_string()
+        })
+        .collect();
+    assert_eq!(files, vec![format!("{}/README.md", &workspace_name)]);
+}
+
+#[tokio::test]
+async fn export_workspace_returns_404_on_tar_gz_with_non_existing_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/api/workspaces/{}/export?format=tar.gz&branch=new_branch",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+fn copy_file(workspace: &Path) -> std::io::Result<u64> {
+    std::fs::copy("templates/default/README.md", workspace.join("README.md"))
+}
+
+fn create_second_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
+    let first_commit = find_last_commit(repository)?;
+    let mut index = repository.index()?;
+    index.add_all(["*"].iter(), IndexAddOption::DEFAULT, None)?;
+    index.write()?;
+    let oid = index.write_tree()?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    let tree = repository.find_tree(oid)?;
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "create file",
+        &tree,
+        &[&first_commit],
+    )
+}
+
+fn create_large_commit(repository: &Repository, content: &[u8]) -> Result<git2::Oid, git2::Error> {
+    let parent = find_last_commit(repository)?;
+    let blob = repository.blob(content)?;
+    let mut tree_builder = repository.treebuilder(Some(&parent.tree()?))?;
+    tree_builder.insert("large.bin", blob, 0o100644)?;
+    let tree = repository.find_tree(tree_builder.write()?)?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "add large.bin",
+        &tree,
+        &[&parent],
+    )
+}
+
+fn get_workspace_name() -> String {
+    Uuid::new_v4().to_simple_string()
+}
+
+fn configure_test(workspace_name: &str) -> std::io::Result<PathBuf> {
+    let mut configuration_file = PathBuf::from("tests_execution");
+    configuration_file.push(workspace_name);
+    configuration_file.set_extension("yaml");
+    std::fs::copy("configuration_test.yaml", &configuration_file)?;
+    Ok(configuration_file)
+}
+
+fn cleanup(configuration_file: &Path, configuration: &Settings, workspace_name: &str) {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace.as_path());
+    }
+    if configuration_file.exists() {
+        _ = fs::remove_file(configuration_file);
+    }
+}
+
+fn spawn_app(configuration: &Settings, workspace: &Path) -> String {
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace);
+    }
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let server = run(listener, configuration).expect("Failed to bind address");
+    tokio::spawn(server);
+    format!("http://127.0.0.1:{}", port)
+}
+
+fn get_workspace(configuration: &Settings, workspace_name: &str) -> PathBuf {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    workspace
+}
+
+fn create_git_repository(workspace: &Path) -> Result<Repository, git2::Error> {
+    Repository::init(workspace)
+}
+
+fn create_master_branch(repository: &Repository) -> Result<(), git2::Error> {
+    create_first_commit(repository)?;
+    let (object, reference) = repository.revparse_ext("master")?;
+    match reference {
+        Some(gref) => repository.set_head(gref.name().unwrap())?,
+        None => repository.set_head_detached(object.id())?,
+    };
+    Ok(())
+}
+
+fn create_first_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
+    let mut index = repository.index()?;
+    let oid = index.write_tree()?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+
+    let tree = repository.find_tree(oid)?;
+
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "commit message",
+        &tree,
+        &[],
+    )
+}
+
+fn find_last_commit(repo: &Repository) -> Result<Commit<'_>, git2::Error> {
+    let obj = repo.head()?.resolve()?.peel(ObjectType::Commit)?;
+    obj.into_commit()
+        .map_err(|_| git2::Error::from_str("Couldn't find commit"))
+}
@@ -0,0 +1,399 @@
+use docversions::configuration::{get_configuration, Settings};
+use docversions::startup::run;
+use git2::{BranchType, Commit, ObjectType, Repository, Signature};
+use reqwest::multipart;
+use std::fs;
+use std::io::Write;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn import_workspace_returns_409_on_already_existing_workspace() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+    let form = multipart::Form::new().text("path", workspace.to_str().unwrap().to_string());
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/import",
+            &address, &workspace_name
+        ))
+        .multipart(form)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 409);
+}
+
+#[tokio::test]
+async fn import_workspace_returns_400_on_missing_source() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let client = reqwest::Client::new();
+    let form = multipart::Form::new().text("commit_message", "import".to_string());
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/import",
+            &address, &workspace_name
+        ))
+        .multipart(form)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let workspace_exists = workspace.exists();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 400);
+    assert!(!workspace_exists);
+}
+
+#[tokio::test]
+async fn import_workspace_from_git_bundle_keeps_branches_and_commits() {
+    let workspace_name = get_workspace_name();
+    let new_workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let last_commit = find_last_commit(&repository).unwrap_or_else(|e| {
+        panic!("Error while retrieving the last commit: {:?}", e);
+    });
+    assert!(repository.branch("new_branch", &last_commit, false).is_ok());
+    let last_commit_id = last_commit.id();
+    let client = reqwest::Client::new();
+    let bundle = client
+        .get(format!(
+            "{}/api/workspaces/{}/export",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .bytes()
+        .await
+        .expect("failed to get payload");
+    let part = multipart::Part::bytes(bundle.to_vec()).file_name("workspace.bundle");
+    let form = multipart::Form::new().part("bundle", part);
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/import",
+            &address, &new_workspace_name
+        ))
+        .multipart(form)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let new_repository = Repository::open(get_workspace(&configuration, &new_workspace_name))
+        .unwrap_or_else(|e| {
+            panic!("Error while opening the imported repository: {:?}", e);
+        });
+    let master_commit_id = get_branch_commit_id(&new_repository, "master");
+    let new_branch_commit_id = get_branch_commit_id(&new_repository, "new_branch");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    cleanup(&configuration_file, &configuration, &new_workspace_name);
+    assert!(response.status().is_success());
+    assert_eq!(master_commit_id, Some(last_commit_id));
+    assert_eq!(new_branch_commit_id, Some(last_commit_id));
+}
+
+#[tokio::test]
+async fn import_workspace_from_zip_archive_creates_initial_commit() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let client = reqwest::Client::new();
+    let archive = create_zip_archive(&[("README.md", "readme"), ("docs/policy.md", "policy")]);
+    let part = multipart::Part::bytes(archive).file_name("documents.zip");
+    let form = multipart::Form::new()
+        .text("commit_message", "import documents".to_string())
+        .part("archive", part);
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/import",
+            &address, &workspace_name
+        ))
+        .multipart(form)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let repository = Repository::open(&workspace).unwrap_or_else(|e| {
+        panic!("Error while opening the imported repository: {:?}", e);
+    });
+    let last_commit = find_last_commit(&repository).unwrap_or_else(|e| {
+        panic!("Error while retrieving the last commit: {:?}", e);
+    });
+    let tree = last_commit.tree().unwrap();
+    let readme = tree.get_path(Path::new("README.md")).is_ok();
+    let policy = tree.get_path(Path::new("docs/policy.md")).is_ok();
+    let summary = last_commit.summary().map(|s| s.to_string());
+    let parent_count = last_commit.parent_count();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(response.status().is_success());
+    assert_eq!(summary, Some("import documents".to_string()));
+    assert_eq!(parent_count, 0);
+    assert!(readme);
+    assert!(policy);
+}
+
+#[tokio::test]
+async fn import_workspace_from_allowed_path_keeps_commits() {
+    let workspace_name = get_workspace_name();
+    let new_workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let last_commit_id = find_last_commit(&repository).map(|c| c.id()).ok();
+    let client = reqwest::Client::new();
+    let form = multipart::Form::new().text("path", workspace.to_str().unwrap().to_string());
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/import",
+            &address, &new_workspace_name
+        ))
+        .multipart(form)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let new_repository = Repository::open(get_workspace(&configuration, &new_workspace_name))
+        .unwrap_or_else(|e| {
+            panic!("Error while opening the imported repository: {:?}", e);
+        });
+    let master_commit_id = get_branch_commit_id(&new_repository, "master");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    cleanup(&configuration_file, &configuration, &new_workspace_name);
+    assert!(response.status().is_success());
+    assert_eq!(master_commit_id, last_commit_id);
+}
+
+#[tokio::test]
+async fn imported_workspace_without_master_uses_its_head_branch_as_default_branch() {
+    let workspace_name = get_workspace_name();
+    let new_workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    assert!(repository.set_head("refs/heads/main").is_ok());
+    let main_commit_id = create_first_commit(&repository).ok();
+    let client = reqwest::Client::new();
+    let form = multipart::Form::new().text("path", workspace.to_str().unwrap().to_string());
+    let import_response = client
+        .post(format!(
+            "{}/api/workspaces/{}/import",
+            &address, &new_workspace_name
+        ))
+        .multipart(form)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let create_branch_response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/feature",
+            &address, &new_workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let rebase_response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/feature/rebase",
+            &address, &new_workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let new_repository = Repository::open(get_workspace(&configuration, &new_workspace_name))
+        .unwrap_or_else(|e| {
+            panic!("Error while opening the imported repository: {:?}", e);
+        });
+    let feature_commit_id = get_branch_commit_id(&new_repository, "feature");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    cleanup(&configuration_file, &configuration, &new_workspace_name);
+    assert!(import_response.status().is_success());
+    assert!(create_branch_response.status().is_success());
+    assert!(rebase_response.status().is_success());
+    assert_eq!(feature_commit_id, main_commit_id);
+}
+
+#[tokio::test]
+async fn import_workspace_returns_403_on_path_outside_the_allowlist() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let client = reqwest::Client::new();
+    let form = multipart::Form::new().text("path", "/tmp/../tmp".to_string());
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/import",
+            &address, &workspace_name
+        ))
+        .multipart(form)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let workspace_exists = workspace.exists();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 403);
+    assert!(!workspace_exists);
+}
+
+fn create_zip_archive(files: &[(&str, &str)]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, content) in files {
+        writer
+            .start_file(*name, zip::write::FileOptions::default())
+            .unwrap();
+        writer.write_all(content.as_bytes()).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+fn get_branch_commit_id(repository: &Repository, branch_name: &str) -> Option<git2::Oid> {
+    repository
+        .find_branch(branch_name, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .map(|c| c.id())
+        .ok()
+}
+
+fn get_workspace_name() -> String {
+    Uuid::new_v4().to_simple_string()
+}
+
+fn configure_test(workspace_name: &str) -> std::io::Result<PathBuf> {
+    let mut configuration_file = PathBuf::from("tests_execution");
+    configuration_file.push(workspace_name);
+    configuration_file.set_extension("yaml");
+    std::fs::copy("configuration_test.yaml", &configuration_file)?;
+    Ok(configuration_file)
+}
+
+fn cleanup(configuration_file: &Path, configuration: &Settings, workspace_name: &str) {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace.as_path());
+    }
+    if configuration_file.exists() {
+        _ = fs::remove_file(configuration_file);
+    }
+}
+
+fn spawn_app(configuration: &Settings, workspace: &Path) -> String {
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace);
+    }
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let server = run(listener, configuration).expect("Failed to bind address");
+    tokio::spawn(server);
+    format!("http://127.0.0.1:{}", port)
+}
+
+fn get_workspace(configuration: &Settings, workspace_name: &str) -> PathBuf {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    workspace
+}
+
+fn create_git_repository(workspace: &Path) -> Result<Repository, git2::Error> {
+    Repository::init(workspace)
+}
+
+fn create_master_branch(repository: &Repository) -> Result<(), git2::Error> {
+    create_first_commit(repository)?;
+    let (object, reference) = repository.revparse_ext("master")?;
+    match reference {
+        Some(gref) => repository.set_head(gref.name().unwrap())?,
+        None => repository.set_head_detached(object.id())?,
+    };
+    Ok(())
+}
+
+fn create_first_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
+    let mut index = repository.index()?;
+    let oid = index.write_tree()?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+
+    let tree = repository.find_tree(oid)?;
+
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "commit message",
+        &tree,
+        &[],
+    )
+}
+
+fn find_last_commit(repo: &Repository) -> Result<Commit<'_>, git2::Error> {
+    let obj = repo.head()?.resolve()?.peel(ObjectType::Commit)?;
+    obj.into_commit()
+        .map_err(|_| git2::Error::from_str("Couldn't find commit"))
+}
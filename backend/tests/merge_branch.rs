@@ -0,0 +1,350 @@
+use docversions::configuration::{get_configuration, Settings};
+use docversions::startup::run;
+use git2::{BranchType, Commit, ObjectType, Oid, Repository, Signature};
+use std::collections::HashMap;
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn merge_branch_fast_forwards_target_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    assert!(create_branch(&repository, "draft").is_ok());
+    let draft_tip = create_branch_commit(&repository, "draft", "README.md", "draft\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/master/merge",
+            &address, &workspace_name
+        ))
+        .json(&HashMap::from([("source", "draft")]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let status = response.status();
+    let merge_result: MergeResult = response.json().await.expect("failed to get payload");
+
+    let master_tip = get_branch_tip(&repository, "master");
+    let readme = fs::read_to_string(workspace.join("README.md"));
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert_eq!(merge_result.status, "fast_forward");
+    assert_eq!(merge_result.commit_uuid, draft_tip.to_string());
+    assert_eq!(master_tip, Some(draft_tip));
+    assert_eq!(readme.ok(), Some("draft\n".to_string()));
+}
+
+#[tokio::test]
+async fn merge_branch_creates_merge_commit_on_diverged_branches() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    assert!(create_branch(&repository, "draft").is_ok());
+    let draft_tip = create_branch_commit(&repository, "draft", "DRAFT.md", "draft\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let master_tip = create_branch_commit(&repository, "master", "README.md", "master\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/master/merge",
+            &address, &workspace_name
+        ))
+        .json(&HashMap::from([("source", "draft")]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let status = response.status();
+    let merge_result: MergeResult = response.json().await.expect("failed to get payload");
+
+    let merge_commit = repository
+        .find_commit(Oid::from_str(&merge_result.commit_uuid).unwrap())
+        .unwrap_or_else(|e| {
+            panic!("Error while retrieving the merge commit: {:?}", e);
+        });
+    let parents: Vec<Oid> = merge_commit.parent_ids().collect();
+    let new_master_tip = get_branch_tip(&repository, "master");
+    let draft_exists = workspace.join("DRAFT.md").exists();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert_eq!(merge_result.status, "merge_commit");
+    assert_eq!(parents, vec![master_tip, draft_tip]);
+    assert_eq!(new_master_tip, Some(merge_commit.id()));
+    assert!(draft_exists);
+}
+
+#[tokio::test]
+async fn merge_branch_returns_up_to_date_on_merged_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    assert!(create_branch(&repository, "draft").is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/master/merge",
+            &address, &workspace_name
+        ))
+        .json(&HashMap::from([("source", "draft")]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let status = response.status();
+    let merge_result: MergeResult = response.json().await.expect("failed to get payload");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert_eq!(merge_result.status, "up_to_date");
+}
+
+#[tokio::test]
+async fn merge_branch_returns_409_with_conflicted_files() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    assert!(create_branch_commit(&repository, "master", "README.md", "base\n").is_ok());
+    assert!(create_branch(&repository, "draft").is_ok());
+    assert!(create_branch_commit(&repository, "draft", "README.md", "draft\n").is_ok());
+    let master_tip = create_branch_commit(&repository, "master", "README.md", "master\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/master/merge",
+            &address, &workspace_name
+        ))
+        .json(&HashMap::from([("source", "draft")]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let status = response.status();
+    let merge_conflicts: MergeConflicts = response.json().await.expect("failed to get payload");
+
+    let new_master_tip = get_branch_tip(&repository, "master");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(status.as_u16(), 409);
+    assert_eq!(merge_conflicts.conflicts.len(), 1);
+    assert_eq!(merge_conflicts.conflicts[0].path, "README.md");
+    assert_eq!(merge_conflicts.conflicts[0].kind, "both_modified");
+    assert_eq!(new_master_tip, Some(master_tip));
+}
+
+#[tokio::test]
+async fn merge_branch_returns_404_on_non_existing_source_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/master/merge",
+            &address, &workspace_name
+        ))
+        .json(&HashMap::from([("source", "draft")]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct MergeResult {
+    status: String,
+    commit_uuid: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct MergeConflicts {
+    conflicts: Vec<MergeConflict>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct MergeConflict {
+    path: String,
+    kind: String,
+}
+
+fn get_workspace_name() -> String {
+    Uuid::new_v4().to_simple_string()
+}
+
+fn configure_test(workspace_name: &str) -> std::io::Result<PathBuf> {
+    let mut configuration_file = PathBuf::from("tests_execution");
+    configuration_file.push(workspace_name);
+    configuration_file.set_extension("yaml");
+    std::fs::copy("configuration_test.yaml", &configuration_file)?;
+    Ok(configuration_file)
+}
+
+fn cleanup(configuration_file: &Path, configuration: &Settings, workspace_name: &str) {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace.as_path());
+    }
+    if configuration_file.exists() {
+        _ = fs::remove_file(configuration_file);
+    }
+}
+
+fn spawn_app(configuration: &Settings, workspace: &Path) -> String {
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace);
+    }
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let server = run(listener, configuration).expect("Failed to bind address");
+    tokio::spawn(server);
+    format!("http://127.0.0.1:{}", port)
+}
+
+fn get_workspace(configuration: &Settings, workspace_name: &str) -> PathBuf {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    workspace
+}
+
+fn create_git_repository(workspace: &Path) -> Result<Repository, git2::Error> {
+    Repository::init(workspace)
+}
+
+fn create_master_branch(repository: &Repository) -> Result<(), git2::Error> {
+    create_first_commit(repository)?;
+    let (object, reference) = repository.revparse_ext("master")?;
+    match reference {
+        Some(gref) => repository.set_head(gref.name().unwrap())?,
+        None => repository.set_head_detached(object.id())?,
+    };
+    Ok(())
+}
+
+fn create_first_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
+    let mut index = repository.index()?;
+    let oid = index.write_tree()?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+
+    let tree = repository.find_tree(oid)?;
+
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "commit message",
+        &tree,
+        &[],
+    )
+}
+
+fn find_last_commit(repo: &Repository) -> Result<Commit<'_>, git2::Error> {
+    let obj = repo.head()?.resolve()?.peel(ObjectType::Commit)?;
+    obj.into_commit()
+        .map_err(|_| git2::Error::from_str("Couldn't find commit"))
+}
+
+fn create_branch(repository: &Repository, branch_name: &str) -> Result<(), git2::Error> {
+    let last_commit = find_last_commit(repository)?;
+    repository.branch(branch_name, &last_commit, false)?;
+    Ok(())
+}
+
+fn create_branch_commit(
+    repository: &Repository,
+    branch_name: &str,
+    file_name: &str,
+    content: &str,
+) -> Result<Oid, git2::Error> {
+    let parent = repository
+        .find_branch(branch_name, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let blob = repository.blob(content.as_bytes())?;
+    let mut tree_builder = repository.treebuilder(Some(&parent.tree()?))?;
+    tree_builder.insert(file_name, blob, 0o100644)?;
+    let tree = repository.find_tree(tree_builder.write()?)?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    repository.commit(
+        Some(&format!("refs/heads/{}", branch_name)),
+        &signature,
+        &signature,
+        &format!("update {}", file_name),
+        &tree,
+        &[&parent],
+    )
+}
+
+fn get_branch_tip(repository: &Repository, branch_name: &str) -> Option<Oid> {
+    repository
+        .find_branch(branch_name, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .map(|c| c.id())
+        .ok()
+}
@@ -0,0 +1,493 @@
+use docversions::configuration::{get_configuration, Settings};
+use docversions::startup::run;
+use git2::{BranchType, Commit, ObjectType, Oid, Repository, Signature};
+use reqwest::multipart;
+use std::collections::HashMap;
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn merge_session_exposes_conflicted_file_versions() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    assert!(create_conflicting_branches(&repository).is_ok());
+    let client = reqwest::Client::new();
+    let session_id = start_merge(&client, &address, &workspace_name).await;
+
+    let response = client
+        .get(format!(
+            "{}/api/workspaces/{}/merges/{}/files/README.md",
+            &address, &workspace_name, &session_id
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let status = response.status();
+    let conflict_file: ConflictFileContent = response.json().await.expect("failed to get payload");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert_eq!(conflict_file.base, Some("base\n".to_string()));
+    assert_eq!(conflict_file.ours, Some("master\n".to_string()));
+    assert_eq!(conflict_file.theirs, Some("draft\n".to_string()));
+    assert_eq!(conflict_file.resolved, None);
+}
+
+#[tokio::test]
+async fn merge_session_commits_resolved_files() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let (master_tip, draft_tip) = create_conflicting_branches(&repository).unwrap_or_else(|e| {
+        panic!("Error while creating the branches: {:?}", e);
+    });
+    let client = reqwest::Client::new();
+    let session_id = start_merge(&client, &address, &workspace_name).await;
+
+    let unresolved_response = client
+        .post(format!(
+            "{}/api/workspaces/{}/merges/{}/commit",
+            &address, &workspace_name, &session_id
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let part = multipart::Part::bytes("master and draft\n".as_bytes())
+        .file_name("README.md")
+        .mime_str("text/plain")
+        .expect("Failed to create the multipart form.");
+    let resolve_response = client
+        .put(format!(
+            "{}/api/workspaces/{}/merges/{}/files/README.md",
+            &address, &workspace_name, &session_id
+        ))
+        .multipart(multipart::Form::new().part("file", part))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let commit_response = client
+        .post(format!(
+            "{}/api/workspaces/{}/merges/{}/commit",
+            &address, &workspace_name, &session_id
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let commit_status = commit_response.status();
+    let merge_commit: MergeSessionCommit =
+        commit_response.json().await.expect("failed to get payload");
+    let session_response = client
+        .get(format!(
+            "{}/api/workspaces/{}/merges/{}",
+            &address, &workspace_name, &session_id
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let merge_commit_id = Oid::from_str(&merge_commit.commit_uuid).unwrap();
+    let parents: Vec<Oid> = repository
+        .find_commit(merge_commit_id)
+        .map(|c| c.parent_ids().collect())
+        .unwrap_or_default();
+    let master_branch_tip = repository
+        .find_branch("master", BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .map(|c| c.id());
+    let readme = fs::read_to_string(workspace.join("README.md"));
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(unresolved_response.status().as_u16(), 409);
+    assert!(resolve_response.status().is_success());
+    assert!(commit_status.is_success());
+    assert_eq!(parents, vec![master_tip, draft_tip]);
+    assert_eq!(master_branch_tip.ok(), Some(merge_commit_id));
+    assert_eq!(readme.ok(), Some("master and draft\n".to_string()));
+    assert_eq!(session_response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn merge_session_abort_keeps_target_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let (master_tip, _) = create_conflicting_branches(&repository).unwrap_or_else(|e| {
+        panic!("Error while creating the branches: {:?}", e);
+    });
+    let client = reqwest::Client::new();
+    let session_id = start_merge(&client, &address, &workspace_name).await;
+
+    let response = client
+        .delete(format!(
+            "{}/api/workspaces/{}/merges/{}",
+            &address, &workspace_name, &session_id
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let sessions: Vec<MergeSession> = client
+        .get(format!(
+            "{}/api/workspaces/{}/merges",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json()
+        .await
+        .expect("failed to get payload");
+
+    let master_branch_tip = repository
+        .find_branch("master", BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .map(|c| c.id());
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(response.status().is_success());
+    assert!(sessions.is_empty());
+    assert_eq!(master_branch_tip.ok(), Some(master_tip));
+}
+
+#[tokio::test]
+async fn merge_session_survives_server_restart() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    assert!(create_conflicting_branches(&repository).is_ok());
+    let client = reqwest::Client::new();
+    let session_id = start_merge(&client, &address, &workspace_name).await;
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let server = run(listener, &configuration).expect("Failed to bind address");
+    tokio::spawn(server);
+    let restarted_address = format!("http://127.0.0.1:{}", port);
+
+    let response = client
+        .get(format!(
+            "{}/api/workspaces/{}/merges/{}",
+            &restarted_address, &workspace_name, &session_id
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let status = response.status();
+    let session: MergeSession = response.json().await.expect("failed to get payload");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert_eq!(session.id, session_id);
+    assert_eq!(session.source, "draft");
+    assert_eq!(session.target, "master");
+    assert_eq!(session.files.len(), 1);
+}
+
+#[tokio::test]
+async fn merge_session_resolves_files_inside_directories() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let create_result = create_directory_commit(&repository, "master", "base\n")
+        .and_then(|_| create_branch(&repository, "draft"))
+        .and_then(|_| create_directory_commit(&repository, "draft", "draft\n"))
+        .and_then(|_| create_directory_commit(&repository, "master", "master\n"));
+    assert!(create_result.is_ok());
+    let client = reqwest::Client::new();
+    let session_id = start_merge(&client, &address, &workspace_name).await;
+
+    let retrieve_response = client
+        .get(format!(
+            "{}/api/workspaces/{}/merges/{}/files?path=d/x",
+            &address, &workspace_name, &session_id
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let retrieve_status = retrieve_response.status();
+    let conflict_file: ConflictFileContent = retrieve_response
+        .json()
+        .await
+        .expect("failed to get payload");
+    let part = multipart::Part::bytes("master and draft\n".as_bytes())
+        .file_name("x")
+        .mime_str("text/plain")
+        .expect("Failed to create the multipart form.");
+    let resolve_response = client
+        .put(format!(
+            "{}/api/workspaces/{}/merges/{}/files?path=d/x",
+            &address, &workspace_name, &session_id
+        ))
+        .multipart(multipart::Form::new().part("file", part))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let traversal_response = client
+        .delete(format!(
+            "{}/api/workspaces/{}/merges/{}/files?path=d/../x",
+            &address, &workspace_name, &session_id
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let commit_response = client
+        .post(format!(
+            "{}/api/workspaces/{}/merges/{}/commit",
+            &address, &workspace_name, &session_id
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let content = repository
+        .find_branch("master", BranchType::Local)
+        .and_then(|b| b.get().peel_to_tree())
+        .and_then(|t| t.get_path(Path::new("d/x")).map(|e| e.id()))
+        .and_then(|oid| repository.find_blob(oid))
+        .map(|b| String::from_utf8_lossy(b.content()).to_string());
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(retrieve_status.is_success());
+    assert_eq!(conflict_file.theirs, Some("draft\n".to_string()));
+    assert!(resolve_response.status().is_success());
+    assert_eq!(traversal_response.status().as_u16(), 400);
+    assert!(commit_response.status().is_success());
+    assert_eq!(content.ok(), Some("master and draft\n".to_string()));
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct MergeConflicts {
+    session_id: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct MergeSession {
+    id: String,
+    source: String,
+    target: String,
+    files: Vec<ConflictFile>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ConflictFile {}
+
+#[derive(Debug, serde::Deserialize)]
+struct ConflictFileContent {
+    base: Option<String>,
+    ours: Option<String>,
+    theirs: Option<String>,
+    resolved: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct MergeSessionCommit {
+    commit_uuid: String,
+}
+
+async fn start_merge(client: &reqwest::Client, address: &str, workspace_name: &str) -> String {
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/master/merge",
+            address, workspace_name
+        ))
+        .json(&HashMap::from([("source", "draft")]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status().as_u16(), 409);
+    let merge_conflicts: MergeConflicts = response.json().await.expect("failed to get payload");
+    merge_conflicts.session_id
+}
+
+fn create_conflicting_branches(repository: &Repository) -> Result<(Oid, Oid), git2::Error> {
+    create_branch_commit(repository, "master", "README.md", "base\n")?;
+    create_branch(repository, "draft")?;
+    let draft_tip = create_branch_commit(repository, "draft", "README.md", "draft\n")?;
+    let master_tip = create_branch_commit(repository, "master", "README.md", "master\n")?;
+    repository.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))?;
+    Ok((master_tip, draft_tip))
+}
+
+fn get_workspace_name() -> String {
+    Uuid::new_v4().to_simple_string()
+}
+
+fn configure_test(workspace_name: &str) -> std::io::Result<PathBuf> {
+    let mut configuration_file = PathBuf::from("tests_execution");
+    configuration_file.push(workspace_name);
+    configuration_file.set_extension("yaml");
+    std::fs::copy("configuration_test.yaml", &configuration_file)?;
+    Ok(configuration_file)
+}
+
+fn cleanup(configuration_file: &Path, configuration: &Settings, workspace_name: &str) {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace.as_path());
+    }
+    if configuration_file.exists() {
+        _ = fs::remove_file(configuration_file);
+    }
+}
+
+fn spawn_app(configuration: &Settings, workspace: &Path) -> String {
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace);
+    }
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let server = run(listener, configuration).expect("Failed to bind address");
+    tokio::spawn(server);
+    format!("http://127.0.0.1:{}", port)
+}
+
+fn get_workspace(configuration: &Settings, workspace_name: &str) -> PathBuf {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    workspace
+}
+
+fn create_git_repository(workspace: &Path) -> Result<Repository, git2::Error> {
+    Repository::init(workspace)
+}
+
+fn create_master_branch(repository: &Repository) -> Result<(), git2::Error> {
+    create_first_commit(repository)?;
+    let (object, reference) = repository.revparse_ext("master")?;
+    match reference {
+        Some(gref) => repository.set_head(gref.name().unwrap())?,
+        None => repository.set_head_detached(object.id())?,
+    };
+    Ok(())
+}
+
+fn create_first_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
+    let mut index = repository.index()?;
+    let oid = index.write_tree()?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+
+    let tree = repository.find_tree(oid)?;
+
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "commit message",
+        &tree,
+        &[],
+    )
+}
+
+fn find_last_commit(repo: &Repository) -> Result<Commit<'_>, git2::Error> {
+    let obj = repo.head()?.resolve()?.peel(ObjectType::Commit)?;
+    obj.into_commit()
+        .map_err(|_| git2::Error::from_str("Couldn't find commit"))
+}
+
+fn create_branch(repository: &Repository, branch_name: &str) -> Result<(), git2::Error> {
+    let last_commit = find_last_commit(repository)?;
+    repository.branch(branch_name, &last_commit, false)?;
+    Ok(())
+}
+
+fn create_branch_commit(
+    repository: &Repository,
+    branch_name: &str,
+    file_name: &str,
+    content: &str,
+) -> Result<Oid, git2::Error> {
+    let parent = repository
+        .find_branch(branch_name, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let blob = repository.blob(content.as_bytes())?;
+    let mut tree_builder = repository.treebuilder(Some(&parent.tree()?))?;
+    tree_builder.insert(file_name, blob, 0o100644)?;
+    let tree = repository.find_tree(tree_builder.write()?)?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    repository.commit(
+        Some(&format!("refs/heads/{}", branch_name)),
+        &signature,
+        &signature,
+        &format!("update {}", file_name),
+        &tree,
+        &[&parent],
+    )
+}
+
+fn create_directory_commit(
+    repository: &Repository,
+    branch_name: &str,
+    content: &str,
+) -> Result<(), git2::Error> {
+    let parent = repository
+        .find_branch(branch_name, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let blob = repository.blob(content.as_bytes())?;
+    let mut directory_builder = repository.treebuilder(None)?;
+    directory_builder.insert("x", blob, 0o100644)?;
+    let directory = directory_builder.write()?;
+    let mut tree_builder = repository.treebuilder(Some(&parent.tree()?))?;
+    tree_builder.insert("d", directory, 0o040000)?;
+    let tree = repository.find_tree(tree_builder.write()?)?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    repository.commit(
+        Some(&format!("refs/heads/{}", branch_name)),
+        &signature,
+        &signature,
+        "update d/x",
+        &tree,
+        &[&parent],
+    )?;
+    Ok(())
+}
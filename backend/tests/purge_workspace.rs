@@ -0,0 +1,206 @@
+use docversions::configuration::{get_configuration, Settings};
+use docversions::startup::run;
+use git2::{Repository, Signature};
+use serial_test::serial;
+use std::fs;
+use std::io::Write;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use uuid::Uuid;
+
+#[tokio::test]
+#[serial]
+async fn purge_workspace_returns_200_and_removes_the_trash_entry() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let trash_entry = delete_workspace(&address, &workspace_name).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .delete(format!("{}/api/trash/{}", &address, &trash_entry.id))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let trash_entry_exists = get_trash_directory(&configuration)
+        .join(&trash_entry.id)
+        .exists();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(response.status().is_success());
+    assert!(!trash_entry_exists);
+}
+
+#[tokio::test]
+#[serial]
+async fn purge_workspace_returns_404_on_non_existing_trash_entry() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .delete(format!("{}/api/trash/1-{}", &address, &workspace_name))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+#[serial]
+async fn purge_workspace_removes_expired_trash_entries_in_background() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let mut configuration_content = fs::OpenOptions::new()
+        .append(true)
+        .open(&configuration_file)
+        .expect("Failed to open the configuration file.");
+    writeln!(
+        configuration_content,
+        "\ntrash_retention_days: 0\ntrash_purge_interval_seconds: 1"
+    )
+    .expect("Failed to update the configuration file.");
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+
+    let trash_entry = delete_workspace(&address, &workspace_name).await;
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+
+    let trash_entry_exists = get_trash_directory(&configuration)
+        .join(&trash_entry.id)
+        .exists();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(!trash_entry_exists);
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct TrashEntry {
+    id: String,
+}
+
+async fn delete_workspace(address: &str, workspace_name: &str) -> TrashEntry {
+    reqwest::Client::new()
+        .delete(format!("{}/api/workspaces/{}", address, workspace_name))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json()
+        .await
+        .expect("Failed to parse the trash entry.")
+}
+
+fn get_workspace_name() -> String {
+    Uuid::new_v4().to_simple_string()
+}
+
+fn configure_test(workspace_name: &str) -> std::io::Result<PathBuf> {
+    let mut configuration_file = PathBuf::from("tests_execution");
+    configuration_file.push(workspace_name);
+    configuration_file.set_extension("yaml");
+    std::fs::copy("configuration_test.yaml", &configuration_file)?;
+    Ok(configuration_file)
+}
+
+fn cleanup(configuration_file: &Path, configuration: &Settings, workspace_name: &str) {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace.as_path());
+    }
+    if configuration_file.exists() {
+        _ = fs::remove_file(configuration_file);
+    }
+    if let Ok(entries) = fs::read_dir(get_trash_directory(configuration)) {
+        entries
+            .filter_map(Result::ok)
+            .filter(|e| e.file_name().to_string_lossy().ends_with(workspace_name))
+            .for_each(|e| _ = fs::remove_dir_all(e.path()));
+    }
+}
+
+fn get_trash_directory(configuration: &Settings) -> PathBuf {
+    let mut trash_directory = PathBuf::from(&configuration.workspaces_path);
+    trash_directory.push(".trash");
+    trash_directory
+}
+
+fn spawn_app(configuration: &Settings, workspace: &Path) -> String {
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace);
+    }
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let server = run(listener, configuration).expect("Failed to bind address");
+    tokio::spawn(server);
+    format!("http://127.0.0.1:{}", port)
+}
+
+fn get_workspace(configuration: &Settings, workspace_name: &str) -> PathBuf {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    workspace
+}
+
+fn create_git_repository(workspace: &Path) -> Result<Repository, git2::Error> {
+    Repository::init(workspace)
+}
+
+fn create_master_branch(repository: &Repository) -> Result<(), git2::Error> {
+    create_first_commit(repository)?;
+    let (object, reference) = repository.revparse_ext("master")?;
+    match reference {
+        Some(gref) => repository.set_head(gref.name().unwrap())?,
+        None => repository.set_head_detached(object.id())?,
+    };
+    Ok(())
+}
+
+fn create_first_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
+    let mut index = repository.index()?;
+    let oid = index.write_tree()?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+
+    let tree = repository.find_tree(oid)?;
+
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "commit message",
+        &tree,
+        &[],
+    )
+}
@@ -0,0 +1,753 @@
+use docversions::configuration::{get_configuration, Settings};
+use docversions::startup::run;
+use git2::{BranchType, Commit, ObjectType, Oid, Repository, Signature};
+use reqwest::multipart::{Form, Part};
+use std::collections::HashMap;
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn rebase_branch_replays_commits_onto_new_base() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    assert!(create_branch(&repository, "draft").is_ok());
+    let _first_draft_commit = create_branch_commit(&repository, "draft", "FIRST.md", "first\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let second_draft_commit = create_branch_commit(&repository, "draft", "SECOND.md", "second\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let master_commit = create_branch_commit(&repository, "master", "MASTER.md", "master\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+
+    let response = rebase_branch(&client, &address, &workspace_name, "draft").await;
+    let status = response.status();
+    let rebase_result: RebaseResult = response.json().await.expect("failed to get payload");
+
+    let draft_tip = get_branch_tip(&repository, "draft").unwrap();
+    let first_rebased_commit = get_commit_parent(&repository, draft_tip).unwrap();
+    let rebase_base = get_commit_parent(&repository, first_rebased_commit);
+    let message = repository
+        .find_commit(draft_tip)
+        .map(|c| c.message().unwrap_or_default().to_string())
+        .unwrap_or_default();
+    let master_file = get_branch_file_content(&repository, "draft", "MASTER.md");
+    let second_file = get_branch_file_content(&repository, "draft", "SECOND.md");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert_eq!(rebase_result.status, "completed");
+    assert_eq!(rebase_result.commit_uuid, draft_tip.to_string());
+    assert_ne!(draft_tip, second_draft_commit);
+    assert_eq!(rebase_base, Some(master_commit));
+    assert_eq!(message, "update SECOND.md");
+    assert_eq!(master_file, "master\n");
+    assert_eq!(second_file, "second\n");
+}
+
+#[tokio::test]
+async fn rebase_branch_stops_on_conflicts_and_continues() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let _base_commit = create_branch_commit(&repository, "master", "README.md", "base\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    assert!(create_branch(&repository, "draft").is_ok());
+    let draft_commit = create_branch_commit(&repository, "draft", "README.md", "draft\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let master_commit = create_branch_commit(&repository, "master", "README.md", "master\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+
+    let response = rebase_branch(&client, &address, &workspace_name, "draft").await;
+    let status = response.status();
+    let rebase_state: RebaseState = response.json().await.expect("failed to get payload");
+    let retrieve_response = client
+        .get(format!(
+            "{}/api/workspaces/{}/rebase",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let resolve_response = client
+        .put(format!(
+            "{}/api/workspaces/{}/rebase/files/README.md",
+            &address, &workspace_name
+        ))
+        .multipart(Form::new().part(
+            "file",
+            Part::bytes(b"resolved\n".to_vec()).file_name("README.md"),
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let resolved_state: RebaseState = resolve_response
+        .json()
+        .await
+        .expect("failed to get payload");
+    let continue_response = client
+        .post(format!(
+            "{}/api/workspaces/{}/rebase/continue",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let continue_status = continue_response.status();
+    let rebase_result: RebaseResult = continue_response
+        .json()
+        .await
+        .expect("failed to get payload");
+
+    let draft_tip = get_branch_tip(&repository, "draft").unwrap();
+    let rebase_base = get_commit_parent(&repository, draft_tip);
+    let readme = get_branch_file_content(&repository, "draft", "README.md");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(status.as_u16(), 409);
+    assert_eq!(rebase_state.branch, Some("draft".to_string()));
+    assert_eq!(rebase_state.commit_uuid, Some(draft_commit.to_string()));
+    assert_eq!(rebase_state.conflicts.len(), 1);
+    assert_eq!(rebase_state.conflicts[0].path, "README.md");
+    assert!(retrieve_response.status().is_success());
+    assert!(resolved_state.conflicts.is_empty());
+    assert!(continue_status.is_success());
+    assert_eq!(rebase_result.status, "completed");
+    assert_eq!(rebase_base, Some(master_commit));
+    assert_eq!(readme, "resolved\n");
+}
+
+#[tokio::test]
+async fn abort_rebase_restores_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let _base_commit = create_branch_commit(&repository, "master", "README.md", "base\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    assert!(create_branch(&repository, "draft").is_ok());
+    let draft_commit = create_branch_commit(&repository, "draft", "README.md", "draft\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let master_commit = create_branch_commit(&repository, "master", "README.md", "master\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+    let rebase_response = rebase_branch(&client, &address, &workspace_name, "draft").await;
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/rebase/abort",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let status = response.status();
+    let rebase_result: RebaseResult = response.json().await.expect("failed to get payload");
+    let retrieve_response = client
+        .get(format!(
+            "{}/api/workspaces/{}/rebase",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let draft_tip = get_branch_tip(&repository, "draft");
+    let master_tip = get_branch_tip(&repository, "master");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(rebase_response.status().as_u16(), 409);
+    assert!(status.is_success());
+    assert_eq!(rebase_result.status, "aborted");
+    assert_eq!(rebase_result.commit_uuid, draft_commit.to_string());
+    assert_eq!(retrieve_response.status().as_u16(), 404);
+    assert_eq!(draft_tip, Some(draft_commit));
+    assert_eq!(master_tip, Some(master_commit));
+}
+
+#[tokio::test]
+async fn rebase_branch_returns_up_to_date_on_branch_based_on_master() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    assert!(create_branch(&repository, "draft").is_ok());
+    let draft_commit = create_branch_commit(&repository, "draft", "README.md", "draft\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+
+    let response = rebase_branch(&client, &address, &workspace_name, "draft").await;
+    let status = response.status();
+    let rebase_result: RebaseResult = response.json().await.expect("failed to get payload");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert_eq!(rebase_result.status, "up_to_date");
+    assert_eq!(rebase_result.commit_uuid, draft_commit.to_string());
+}
+
+#[tokio::test]
+async fn rebase_branch_returns_403_on_protected_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let _base_commit = create_branch_commit(&repository, "master", "README.md", "base\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    assert!(create_branch(&repository, "draft").is_ok());
+    let draft_commit = create_branch_commit(&repository, "draft", "README.md", "draft\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let master_commit = create_branch_commit(&repository, "master", "README.md", "master\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let protect_result = repository
+        .config()
+        .and_then(|mut c| c.set_bool("branch.draft.nodeleteorreset", true));
+    assert!(protect_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = rebase_branch(&client, &address, &workspace_name, "draft").await;
+
+    let draft_tip = get_branch_tip(&repository, "draft");
+    let master_tip = get_branch_tip(&repository, "master");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 403);
+    assert_eq!(draft_tip, Some(draft_commit));
+    assert_eq!(master_tip, Some(master_commit));
+}
+
+#[tokio::test]
+async fn rebase_branch_resolves_files_inside_directories() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let create_result = create_directory_commit(&repository, "master", "base\n")
+        .and_then(|_| create_branch(&repository, "draft"))
+        .and_then(|_| create_directory_commit(&repository, "draft", "draft\n"))
+        .and_then(|_| create_directory_commit(&repository, "master", "master\n"));
+    assert!(create_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = rebase_branch(&client, &address, &workspace_name, "draft").await;
+    let status = response.status();
+    let rebase_state: RebaseState = response.json().await.expect("failed to get payload");
+    let unrelated_response = client
+        .put(format!(
+            "{}/api/workspaces/{}/rebase/files?path=d/y",
+            &address, &workspace_name
+        ))
+        .multipart(Form::new().part("file", Part::bytes(b"y\n".to_vec()).file_name("y")))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let resolve_response = client
+        .put(format!(
+            "{}/api/workspaces/{}/rebase/files?path=d/x",
+            &address, &workspace_name
+        ))
+        .multipart(Form::new().part("file", Part::bytes(b"resolved\n".to_vec()).file_name("x")))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let resolved_state: RebaseState = resolve_response
+        .json()
+        .await
+        .expect("failed to get payload");
+    let continue_response = client
+        .post(format!(
+            "{}/api/workspaces/{}/rebase/continue",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let content = get_branch_file_content(&repository, "draft", "d/x");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(status.as_u16(), 409);
+    assert_eq!(rebase_state.conflicts.len(), 1);
+    assert_eq!(rebase_state.conflicts[0].path, "d/x");
+    assert_eq!(unrelated_response.status().as_u16(), 404);
+    assert!(resolved_state.conflicts.is_empty());
+    assert!(continue_response.status().is_success());
+    assert_eq!(content, "resolved\n");
+}
+
+#[tokio::test]
+async fn writes_during_stopped_rebase_return_409() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let _base_commit = create_branch_commit(&repository, "master", "README.md", "base\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    assert!(create_branch(&repository, "draft").is_ok());
+    let draft_commit = create_branch_commit(&repository, "draft", "README.md", "draft\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let master_commit = create_branch_commit(&repository, "master", "README.md", "master\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+    let rebase_response = rebase_branch(&client, &address, &workspace_name, "draft").await;
+
+    let create_file_response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/master/files/NEW.md",
+            &address, &workspace_name
+        ))
+        .multipart(
+            Form::new()
+                .text("commit_message", "create file")
+                .part("file", Part::bytes(b"new\n".to_vec()).file_name("NEW.md")),
+        )
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let change_branch_response = client
+        .put(format!(
+            "{}/api/workspaces/{}/branches/master",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let resolve_response = client
+        .put(format!(
+            "{}/api/workspaces/{}/rebase/files/README.md",
+            &address, &workspace_name
+        ))
+        .multipart(Form::new().part(
+            "file",
+            Part::bytes(b"resolved\n".to_vec()).file_name("README.md"),
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let continue_response = client
+        .post(format!(
+            "{}/api/workspaces/{}/rebase/continue",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let draft_tip = get_branch_tip(&repository, "draft").unwrap();
+    let rebase_base = get_commit_parent(&repository, draft_tip);
+    let master_tip = get_branch_tip(&repository, "master");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(rebase_response.status().as_u16(), 409);
+    assert_eq!(create_file_response.status().as_u16(), 409);
+    assert_eq!(change_branch_response.status().as_u16(), 409);
+    assert!(resolve_response.status().is_success());
+    assert!(continue_response.status().is_success());
+    assert_eq!(master_tip, Some(master_commit));
+    assert_eq!(rebase_base, Some(master_commit));
+    assert_ne!(draft_tip, draft_commit);
+}
+
+#[tokio::test]
+async fn continue_rebase_returns_409_when_head_has_moved() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let _base_commit = create_branch_commit(&repository, "master", "README.md", "base\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    assert!(create_branch(&repository, "draft").is_ok());
+    let draft_commit = create_branch_commit(&repository, "draft", "README.md", "draft\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let master_commit = create_branch_commit(&repository, "master", "README.md", "master\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+    let rebase_response = rebase_branch(&client, &address, &workspace_name, "draft").await;
+    let resolve_response = client
+        .put(format!(
+            "{}/api/workspaces/{}/rebase/files/README.md",
+            &address, &workspace_name
+        ))
+        .multipart(Form::new().part(
+            "file",
+            Part::bytes(b"resolved\n".to_vec()).file_name("README.md"),
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(repository.set_head("refs/heads/master").is_ok());
+
+    let continue_response = client
+        .post(format!(
+            "{}/api/workspaces/{}/rebase/continue",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let draft_tip = get_branch_tip(&repository, "draft");
+    let master_tip = get_branch_tip(&repository, "master");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(rebase_response.status().as_u16(), 409);
+    assert!(resolve_response.status().is_success());
+    assert_eq!(continue_response.status().as_u16(), 409);
+    assert_eq!(draft_tip, Some(draft_commit));
+    assert_eq!(master_tip, Some(master_commit));
+}
+
+#[tokio::test]
+async fn rebase_branch_returns_403_on_branch_accepting_only_merges() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let _base_commit = create_branch_commit(&repository, "master", "README.md", "base\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    assert!(create_branch(&repository, "draft").is_ok());
+    let draft_commit = create_branch_commit(&repository, "draft", "README.md", "draft\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let master_commit = create_branch_commit(&repository, "master", "README.md", "master\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let protect_result = repository
+        .config()
+        .and_then(|mut c| c.set_bool("branch.draft.nodirectcommits", true));
+    assert!(protect_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = rebase_branch(&client, &address, &workspace_name, "draft").await;
+
+    let draft_tip = get_branch_tip(&repository, "draft");
+    let master_tip = get_branch_tip(&repository, "master");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 403);
+    assert_eq!(draft_tip, Some(draft_commit));
+    assert_eq!(master_tip, Some(master_commit));
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct RebaseResult {
+    status: String,
+    commit_uuid: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct RebaseState {
+    branch: Option<String>,
+    commit_uuid: Option<String>,
+    conflicts: Vec<MergeConflict>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct MergeConflict {
+    path: String,
+}
+
+fn get_workspace_name() -> String {
+    Uuid::new_v4().to_simple_string()
+}
+
+fn configure_test(workspace_name: &str) -> std::io::Result<PathBuf> {
+    let mut configuration_file = PathBuf::from("tests_execution");
+    configuration_file.push(workspace_name);
+    configuration_file.set_extension("yaml");
+    std::fs::copy("configuration_test.yaml", &configuration_file)?;
+    Ok(configuration_file)
+}
+
+fn cleanup(configuration_file: &Path, configuration: &Settings, workspace_name: &str) {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace.as_path());
+    }
+    if configuration_file.exists() {
+        _ = fs::remove_file(configuration_file);
+    }
+}
+
+fn spawn_app(configuration: &Settings, workspace: &Path) -> String {
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace);
+    }
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let server = run(listener, configuration).expect("Failed to bind address");
+    tokio::spawn(server);
+    format!("http://127.0.0.1:{}", port)
+}
+
+fn get_workspace(configuration: &Settings, workspace_name: &str) -> PathBuf {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    workspace
+}
+
+fn create_git_repository(workspace: &Path) -> Result<Repository, git2::Error> {
+    Repository::init(workspace)
+}
+
+fn create_master_branch(repository: &Repository) -> Result<(), git2::Error> {
+    create_first_commit(repository)?;
+    let (object, reference) = repository.revparse_ext("master")?;
+    match reference {
+        Some(gref) => repository.set_head(gref.name().unwrap())?,
+        None => repository.set_head_detached(object.id())?,
+    };
+    Ok(())
+}
+
+fn create_first_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
+    let mut index = repository.index()?;
+    let oid = index.write_tree()?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+
+    let tree = repository.find_tree(oid)?;
+
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "commit message",
+        &tree,
+        &[],
+    )
+}
+
+fn find_last_commit(repo: &Repository) -> Result<Commit<'_>, git2::Error> {
+    let obj = repo.head()?.resolve()?.peel(ObjectType::Commit)?;
+    obj.into_commit()
+        .map_err(|_| git2::Error::from_str("Couldn't find commit"))
+}
+
+fn create_branch(repository: &Repository, branch_name: &str) -> Result<(), git2::Error> {
+    let last_commit = find_last_commit(repository)?;
+    repository.branch(branch_name, &last_commit, false)?;
+    Ok(())
+}
+
+fn create_branch_commit(
+    repository: &Repository,
+    branch_name: &str,
+    file_name: &str,
+    content: &str,
+) -> Result<Oid, git2::Error> {
+    let parent = repository
+        .find_branch(branch_name, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let blob = repository.blob(content.as_bytes())?;
+    let mut tree_builder = repository.treebuilder(Some(&parent.tree()?))?;
+    tree_builder.insert(file_name, blob, 0o100644)?;
+    let tree = repository.find_tree(tree_builder.write()?)?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    repository.commit(
+        Some(&format!("refs/heads/{}", branch_name)),
+        &signature,
+        &signature,
+        &format!("update {}", file_name),
+        &tree,
+        &[&parent],
+    )
+}
+
+fn create_directory_commit(
+    repository: &Repository,
+    branch_name: &str,
+    content: &str,
+) -> Result<Oid, git2::Error> {
+    let parent = repository
+        .find_branch(branch_name, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let blob = repository.blob(content.as_bytes())?;
+    let mut directory_builder = repository.treebuilder(None)?;
+    directory_builder.insert("x", blob, 0o100644)?;
+    let directory = directory_builder.write()?;
+    let mut tree_builder = repository.treebuilder(Some(&parent.tree()?))?;
+    tree_builder.insert("d", directory, 0o040000)?;
+    let tree = repository.find_tree(tree_builder.write()?)?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    repository.commit(
+        Some(&format!("refs/heads/{}", branch_name)),
+        &signature,
+        &signature,
+        "update d/x",
+        &tree,
+        &[&parent],
+    )
+}
+
+fn get_branch_tip(repository: &Repository, branch_name: &str) -> Option<Oid> {
+    repository
+        .find_branch(branch_name, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .map(|c| c.id())
+        .ok()
+}
+
+fn get_branch_file_content(repository: &Repository, branch_name: &str, file_name: &str) -> String {
+    let commit = repository
+        .find_branch(branch_name, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .unwrap_or_else(|e| {
+            panic!("Error while retrieving the branch: {:?}", e);
+        });
+    let blob = commit
+        .tree()
+        .and_then(|t| t.get_path(Path::new(file_name)))
+        .and_then(|e| e.to_object(repository))
+        .and_then(|o| o.peel_to_blob())
+        .unwrap_or_else(|e| {
+            panic!("Error while retrieving the file: {:?}", e);
+        });
+    String::from_utf8_lossy(blob.content()).to_string()
+}
+
+async fn rebase_branch(
+    client: &reqwest::Client,
+    address: &str,
+    workspace_name: &str,
+    branch_name: &str,
+) -> reqwest::Response {
+    client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/{}/rebase",
+            address, workspace_name, branch_name
+        ))
+        .json(&HashMap::from([("onto", "master")]))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+fn get_commit_parent(repository: &Repository, oid: Oid) -> Option<Oid> {
+    repository
+        .find_commit(oid)
+        .and_then(|c| c.parent_id(0))
+        .ok()
+}
@@ -0,0 +1,256 @@
+use docversions::configuration::{get_configuration, Settings};
+use docversions::startup::run;
+use git2::{BranchType, Commit, ObjectType, Repository, Signature};
+use std::collections::HashMap;
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn rename_branch_returns_200_and_keeps_the_commits() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    assert!(create_branch(&repository, "new_brnach").is_ok());
+    let description_result = repository
+        .config()
+        .and_then(|mut c| c.set_str("branch.new_brnach.description", "First draft"));
+    assert!(description_result.is_ok());
+    let tip = find_last_commit(&repository).map(|c| c.id());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/new_brnach/rename",
+            &address, &workspace_name
+        ))
+        .json(&HashMap::from([("name", "new_branch")]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let status = response.status();
+    let renamed_branch: GitBranch = response.json().await.expect("failed to get payload");
+
+    let old_branch_exists = repository
+        .find_branch("new_brnach", BranchType::Local)
+        .is_ok();
+    let new_branch_tip = repository
+        .find_branch("new_branch", BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .map(|c| c.id());
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert!(!old_branch_exists);
+    assert_eq!(
+        renamed_branch.commit_uuid,
+        tip.as_ref().ok().map(|oid| oid.to_string())
+    );
+    assert_eq!(new_branch_tip.ok(), tip.ok());
+    assert_eq!(renamed_branch.name, "new_branch");
+    assert_eq!(renamed_branch.description, Some("First draft".to_string()));
+}
+
+#[tokio::test]
+async fn rename_branch_moves_head_on_current_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    assert!(create_branch(&repository, "draft").is_ok());
+    assert!(repository.set_head("refs/heads/draft").is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/draft/rename",
+            &address, &workspace_name
+        ))
+        .json(&HashMap::from([("name", "chapter_one")]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let head = repository
+        .head()
+        .map(|h| h.name().unwrap_or_default().to_string());
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(response.status().is_success());
+    assert_eq!(head.ok(), Some("refs/heads/chapter_one".to_string()));
+}
+
+#[tokio::test]
+async fn rename_branch_returns_409_on_already_existing_target_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    assert!(create_branch(&repository, "new_branch").is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/new_branch/rename",
+            &address, &workspace_name
+        ))
+        .json(&HashMap::from([("name", "master")]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let branch_exists = repository
+        .find_branch("new_branch", BranchType::Local)
+        .is_ok();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 409);
+    assert!(branch_exists);
+}
+
+#[tokio::test]
+async fn rename_branch_returns_404_on_non_existing_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/new_branch/rename",
+            &address, &workspace_name
+        ))
+        .json(&HashMap::from([("name", "other_branch")]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitBranch {
+    name: String,
+    commit_uuid: Option<String>,
+    description: Option<String>,
+}
+
+fn get_workspace_name() -> String {
+    Uuid::new_v4().to_simple_string()
+}
+
+fn configure_test(workspace_name: &str) -> std::io::Result<PathBuf> {
+    let mut configuration_file = PathBuf::from("tests_execution");
+    configuration_file.push(workspace_name);
+    configuration_file.set_extension("yaml");
+    std::fs::copy("configuration_test.yaml", &configuration_file)?;
+    Ok(configuration_file)
+}
+
+fn cleanup(configuration_file: &Path, configuration: &Settings, workspace_name: &str) {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace.as_path());
+    }
+    if configuration_file.exists() {
+        _ = fs::remove_file(configuration_file);
+    }
+}
+
+fn spawn_app(configuration: &Settings, workspace: &Path) -> String {
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace);
+    }
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let server = run(listener, configuration).expect("Failed to bind address");
+    tokio::spawn(server);
+    format!("http://127.0.0.1:{}", port)
+}
+
+fn get_workspace(configuration: &Settings, workspace_name: &str) -> PathBuf {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    workspace
+}
+
+fn create_git_repository(workspace: &Path) -> Result<Repository, git2::Error> {
+    Repository::init(workspace)
+}
+
+fn create_master_branch(repository: &Repository) -> Result<(), git2::Error> {
+    create_first_commit(repository)?;
+    let (object, reference) = repository.revparse_ext("master")?;
+    match reference {
+        Some(gref) => repository.set_head(gref.name().unwrap())?,
+        None => repository.set_head_detached(object.id())?,
+    };
+    Ok(())
+}
+
+fn create_first_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
+    let mut index = repository.index()?;
+    let oid = index.write_tree()?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+
+    let tree = repository.find_tree(oid)?;
+
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "commit message",
+        &tree,
+        &[],
+    )
+}
+
+fn find_last_commit(repo: &Repository) -> Result<Commit<'_>, git2::Error> {
+    let obj = repo.head()?.resolve()?.peel(ObjectType::Commit)?;
+    obj.into_commit()
+        .map_err(|_| git2::Error::from_str("Couldn't find commit"))
+}
+
+fn create_branch(repository: &Repository, branch_name: &str) -> Result<(), git2::Error> {
+    let last_commit = find_last_commit(repository)?;
+    repository.branch(branch_name, &last_commit, false)?;
+    Ok(())
+}
@@ -0,0 +1,259 @@
+use docversions::configuration::{get_configuration, Settings};
+use docversions::startup::run;
+use git2::{BranchType, Commit, ObjectType, Repository, Signature};
+use std::collections::HashMap;
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn rename_workspace_returns_200_on_existing_workspace() {
+    let workspace_name = get_workspace_name();
+    let new_workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .put(format!("{}/api/workspaces/{}", &address, &workspace_name))
+        .json(&HashMap::from([("name", &new_workspace_name)]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let new_workspace = get_workspace(&configuration, &new_workspace_name);
+    let workspace_exists = workspace.exists();
+    let new_workspace_exists = new_workspace.exists();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    cleanup(&configuration_file, &configuration, &new_workspace_name);
+    assert!(response.status().is_success());
+    assert!(!workspace_exists);
+    assert!(new_workspace_exists);
+}
+
+#[tokio::test]
+async fn rename_workspace_returns_404_on_non_existing_workspace() {
+    let workspace_name = get_workspace_name();
+    let new_workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let client = reqwest::Client::new();
+
+    let response = client
+        .put(format!("{}/api/workspaces/{}", &address, &workspace_name))
+        .json(&HashMap::from([("name", &new_workspace_name)]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    cleanup(&configuration_file, &configuration, &new_workspace_name);
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn rename_workspace_returns_409_on_already_existing_target_workspace() {
+    let workspace_name = get_workspace_name();
+    let new_workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let new_workspace = get_workspace(&configuration, &new_workspace_name);
+    let new_repository = create_git_repository(&new_workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&new_repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .put(format!("{}/api/workspaces/{}", &address, &workspace_name))
+        .json(&HashMap::from([("name", &new_workspace_name)]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let workspace_exists = workspace.exists();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    cleanup(&configuration_file, &configuration, &new_workspace_name);
+    assert_eq!(response.status().as_u16(), 409);
+    assert!(workspace_exists);
+}
+
+#[tokio::test]
+async fn rename_workspace_keeps_branches_and_commits() {
+    let workspace_name = get_workspace_name();
+    let new_workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let last_commit = find_last_commit(&repository).unwrap_or_else(|e| {
+        panic!("Error while retrieving the last commit: {:?}", e);
+    });
+    let branch = repository.branch("new_branch", &last_commit, false);
+    assert!(branch.is_ok());
+    let last_commit_id = last_commit.id();
+    let client = reqwest::Client::new();
+
+    let _ = client
+        .put(format!("{}/api/workspaces/{}", &address, &workspace_name))
+        .json(&HashMap::from([("name", &new_workspace_name)]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let new_workspace = get_workspace(&configuration, &new_workspace_name);
+    let new_repository = Repository::open(&new_workspace).unwrap_or_else(|e| {
+        panic!("Error while opening the renamed repository: {:?}", e);
+    });
+    let new_branch_commit_id = new_repository
+        .find_branch("new_branch", BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .map(|c| c.id());
+    let new_last_commit_id = find_last_commit(&new_repository).map(|c| c.id());
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    cleanup(&configuration_file, &configuration, &new_workspace_name);
+    assert_eq!(new_branch_commit_id.ok(), Some(last_commit_id));
+    assert_eq!(new_last_commit_id.ok(), Some(last_commit_id));
+}
+
+#[tokio::test]
+async fn rename_workspace_returns_400_on_invalid_target_workspace_name() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .put(format!("{}/api/workspaces/{}", &address, &workspace_name))
+        .json(&HashMap::from([("name", "../escaped")]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let workspace_exists = workspace.exists();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 400);
+    assert!(workspace_exists);
+}
+
+fn get_workspace_name() -> String {
+    Uuid::new_v4().to_simple_string()
+}
+
+fn configure_test(workspace_name: &str) -> std::io::Result<PathBuf> {
+    let mut configuration_file = PathBuf::from("tests_execution");
+    configuration_file.push(workspace_name);
+    configuration_file.set_extension("yaml");
+    std::fs::copy("configuration_test.yaml", &configuration_file)?;
+    Ok(configuration_file)
+}
+
+fn cleanup(configuration_file: &Path, configuration: &Settings, workspace_name: &str) {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace.as_path());
+    }
+    if configuration_file.exists() {
+        _ = fs::remove_file(configuration_file);
+    }
+}
+
+fn spawn_app(configuration: &Settings, workspace: &Path) -> String {
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace);
+    }
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let server = run(listener, configuration).expect("Failed to bind address");
+    tokio::spawn(server);
+    format!("http://127.0.0.1:{}", port)
+}
+
+fn get_workspace(configuration: &Settings, workspace_name: &str) -> PathBuf {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    workspace
+}
+
+fn create_git_repository(workspace: &Path) -> Result<Repository, git2::Error> {
+    Repository::init(workspace)
+}
+
+fn create_master_branch(repository: &Repository) -> Result<(), git2::Error> {
+    create_first_commit(repository)?;
+    let (object, reference) = repository.revparse_ext("master")?;
+    match reference {
+        Some(gref) => repository.set_head(gref.name().unwrap())?,
+        None => repository.set_head_detached(object.id())?,
+    };
+    Ok(())
+}
+
+fn create_first_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
+    let mut index = repository.index()?;
+    let oid = index.write_tree()?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+
+    let tree = repository.find_tree(oid)?;
+
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "commit message",
+        &tree,
+        &[],
+    )
+}
+
+fn find_last_commit(repo: &Repository) -> Result<Commit<'_>, git2::Error> {
+    let obj = repo.head()?.resolve()?.peel(ObjectType::Commit)?;
+    obj.into_commit()
+        .map_err(|_| git2::Error::from_str("Couldn't find commit"))
+}
@@ -0,0 +1,398 @@
+use docversions::configuration::{get_configuration, Settings};
+use docversions::startup::run;
+use git2::{BranchType, Commit, ObjectType, Oid, Repository, Signature};
+use std::collections::HashMap;
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn reset_branch_moves_branch_and_keeps_backup() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let first_commit = create_branch_commit(&repository, "master", "README.md", "first\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let second_commit = create_branch_commit(&repository, "master", "README.md", "second\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+
+    let response = reset_branch(
+        &client,
+        &address,
+        &workspace_name,
+        "master",
+        &first_commit.to_string(),
+    )
+    .await;
+    let status = response.status();
+    let reset_result: ResetResult = response.json().await.expect("failed to get payload");
+    let backups = retrieve_backups(&client, &address, &workspace_name, "master").await;
+
+    let master_tip = get_branch_tip(&repository, "master");
+    let readme = fs::read_to_string(workspace.join("README.md"));
+    let backup_tip = repository
+        .find_reference(&format!("refs/backups/master/{}", &backups[0].id))
+        .and_then(|r| r.peel_to_commit())
+        .map(|c| c.id())
+        .ok();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert_eq!(reset_result.commit_uuid, first_commit.to_string());
+    assert_eq!(master_tip, Some(first_commit));
+    assert_eq!(readme.ok(), Some("first\n".to_string()));
+    assert_eq!(backups.len(), 1);
+    assert_eq!(backups[0].commit_uuid, second_commit.to_string());
+    assert_eq!(
+        Some(&backups[0].id),
+        reset_result.backup.map(|b| b.id).as_ref()
+    );
+    assert_eq!(backup_tip, Some(second_commit));
+}
+
+#[tokio::test]
+async fn restore_branch_backup_moves_branch_back() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let first_commit = create_branch_commit(&repository, "master", "README.md", "first\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let second_commit = create_branch_commit(&repository, "master", "README.md", "second\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+    let reset_response = reset_branch(
+        &client,
+        &address,
+        &workspace_name,
+        "master",
+        &first_commit.to_string(),
+    )
+    .await;
+    let backups = retrieve_backups(&client, &address, &workspace_name, "master").await;
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/master/backups/{}/restore",
+            &address, &workspace_name, &backups[0].id
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let status = response.status();
+    let new_backups = retrieve_backups(&client, &address, &workspace_name, "master").await;
+
+    let master_tip = get_branch_tip(&repository, "master");
+    let readme = fs::read_to_string(workspace.join("README.md"));
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(reset_response.status().is_success());
+    assert!(status.is_success());
+    assert_eq!(master_tip, Some(second_commit));
+    assert_eq!(readme.ok(), Some("second\n".to_string()));
+    assert_eq!(new_backups.len(), 1);
+    assert_eq!(new_backups[0].commit_uuid, first_commit.to_string());
+}
+
+#[tokio::test]
+async fn restore_branch_backup_returns_404_on_non_existing_backup() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/master/backups/1/restore",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn reset_branch_returns_400_on_commit_outside_of_history() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    assert!(create_branch(&repository, "draft").is_ok());
+    let draft_commit = create_branch_commit(&repository, "draft", "README.md", "draft\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+
+    let response = reset_branch(
+        &client,
+        &address,
+        &workspace_name,
+        "master",
+        &draft_commit.to_string(),
+    )
+    .await;
+
+    let backups = retrieve_backups(&client, &address, &workspace_name, "master").await;
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 400);
+    assert!(backups.is_empty());
+}
+
+#[tokio::test]
+async fn reset_branch_returns_403_on_protected_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let first_commit = create_branch_commit(&repository, "master", "README.md", "first\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let second_commit = create_branch_commit(&repository, "master", "README.md", "second\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let protect_result = repository
+        .config()
+        .and_then(|mut c| c.set_bool("branch.master.nodeleteorreset", true));
+    assert!(protect_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = reset_branch(
+        &client,
+        &address,
+        &workspace_name,
+        "master",
+        &first_commit.to_string(),
+    )
+    .await;
+
+    let master_tip = get_branch_tip(&repository, "master");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 403);
+    assert_eq!(master_tip, Some(second_commit));
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ResetResult {
+    commit_uuid: String,
+    backup: Option<BranchBackup>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct BranchBackup {
+    id: String,
+    commit_uuid: String,
+}
+
+fn get_workspace_name() -> String {
+    Uuid::new_v4().to_simple_string()
+}
+
+fn configure_test(workspace_name: &str) -> std::io::Result<PathBuf> {
+    let mut configuration_file = PathBuf::from("tests_execution");
+    configuration_file.push(workspace_name);
+    configuration_file.set_extension("yaml");
+    std::fs::copy("configuration_test.yaml", &configuration_file)?;
+    Ok(configuration_file)
+}
+
+fn cleanup(configuration_file: &Path, configuration: &Settings, workspace_name: &str) {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace.as_path());
+    }
+    if configuration_file.exists() {
+        _ = fs::remove_file(configuration_file);
+    }
+}
+
+fn spawn_app(configuration: &Settings, workspace: &Path) -> String {
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace);
+    }
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let server = run(listener, configuration).expect("Failed to bind address");
+    tokio::spawn(server);
+    format!("http://127.0.0.1:{}", port)
+}
+
+fn get_workspace(configuration: &Settings, workspace_name: &str) -> PathBuf {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    workspace
+}
+
+fn create_git_repository(workspace: &Path) -> Result<Repository, git2::Error> {
+    Repository::init(workspace)
+}
+
+fn create_master_branch(repository: &Repository) -> Result<(), git2::Error> {
+    create_first_commit(repository)?;
+    let (object, reference) = repository.revparse_ext("master")?;
+    match reference {
+        Some(gref) => repository.set_head(gref.name().unwrap())?,
+        None => repository.set_head_detached(object.id())?,
+    };
+    Ok(())
+}
+
+fn create_first_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
+    let mut index = repository.index()?;
+    let oid = index.write_tree()?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+
+    let tree = repository.find_tree(oid)?;
+
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "commit message",
+        &tree,
+        &[],
+    )
+}
+
+fn find_last_commit(repo: &Repository) -> Result<Commit<'_>, git2::Error> {
+    let obj = repo.head()?.resolve()?.peel(ObjectType::Commit)?;
+    obj.into_commit()
+        .map_err(|_| git2::Error::from_str("Couldn't find commit"))
+}
+
+fn create_branch(repository: &Repository, branch_name: &str) -> Result<(), git2::Error> {
+    let last_commit = find_last_commit(repository)?;
+    repository.branch(branch_name, &last_commit, false)?;
+    Ok(())
+}
+
+fn create_branch_commit(
+    repository: &Repository,
+    branch_name: &str,
+    file_name: &str,
+    content: &str,
+) -> Result<Oid, git2::Error> {
+    let parent = repository
+        .find_branch(branch_name, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let blob = repository.blob(content.as_bytes())?;
+    let mut tree_builder = repository.treebuilder(Some(&parent.tree()?))?;
+    tree_builder.insert(file_name, blob, 0o100644)?;
+    let tree = repository.find_tree(tree_builder.write()?)?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    repository.commit(
+        Some(&format!("refs/heads/{}", branch_name)),
+        &signature,
+        &signature,
+        &format!("update {}", file_name),
+        &tree,
+        &[&parent],
+    )
+}
+
+fn get_branch_tip(repository: &Repository, branch_name: &str) -> Option<Oid> {
+    repository
+        .find_branch(branch_name, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .map(|c| c.id())
+        .ok()
+}
+
+async fn reset_branch(
+    client: &reqwest::Client,
+    address: &str,
+    workspace_name: &str,
+    branch_name: &str,
+    commit_uuid: &str,
+) -> reqwest::Response {
+    client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/{}/reset",
+            address, workspace_name, branch_name
+        ))
+        .json(&HashMap::from([("commit_uuid", commit_uuid)]))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+async fn retrieve_backups(
+    client: &reqwest::Client,
+    address: &str,
+    workspace_name: &str,
+    branch_name: &str,
+) -> Vec<BranchBackup> {
+    client
+        .get(format!(
+            "{}/api/workspaces/{}/branches/{}/backups",
+            address, workspace_name, branch_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json()
+        .await
+        .expect("failed to get payload")
+}
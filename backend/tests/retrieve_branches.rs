@@ -1,6 +1,7 @@
 use docversions::configuration::{get_configuration, Settings};
 use docversions::startup::run;
 use git2::{Repository, Signature};
+use std::collections::HashMap;
 use std::fs;
 use std::net::TcpListener;
 use std::path::PathBuf;
@@ -112,6 +113,9 @@ async fn retrieve_branches_returns_master_branch_for_repository_with_master_bran
     });
     let branch_result = create_master_branch(repository);
     assert!(branch_result.is_ok());
+    let first_commit = Repository::open(&workspace)
+        .and_then(|r| r.head()?.peel_to_commit().map(|c| c.id()))
+        .unwrap();
 
     let client = reqwest::Client::new();
 
@@ -123,15 +127,84 @@ async fn retrieve_branches_returns_master_branch_for_repository_with_master_bran
         .send()
         .await
         .expect("Failed to execute request.")
-        .text()
+        .json::<Vec<GitBranch>>()
+        .await
+        .expect("failed to get payload");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.len(), 1);
+    assert_eq!(response[0].name, "master");
+    assert_eq!(response[0].commit_uuid, Some(first_commit.to_string()));
+    assert!(response[0].commit_time.is_some());
+    assert_eq!(response[0].description, None);
+    assert_eq!(response[0].created_at, None);
+}
+
+#[tokio::test]
+async fn retrieve_branches_returns_metadata_of_created_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|e| {
+        panic!("Error while configuring the test: {:?}.", e);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+    let create_response = client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/draft",
+            &address, &workspace_name
+        ))
+        .json(&HashMap::from([
+            ("description", "Draft of the next release"),
+            ("creator", "Marco Cella"),
+        ]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(create_response.status().is_success());
+
+    let response = client
+        .get(format!(
+            "{}/api/workspaces/{}/branches",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Vec<GitBranch>>()
         .await
         .expect("failed to get payload");
 
     cleanup(&configuration_file, &configuration, &workspace_name);
+    let master = response.iter().find(|b| b.name == "master").unwrap();
+    let draft = response.iter().find(|b| b.name == "draft").unwrap();
+    assert_eq!(draft.commit_uuid, master.commit_uuid);
+    assert_eq!(draft.commit_time, master.commit_time);
     assert_eq!(
-        response, "[{\"name\":\"master\"}]",
-        "The body content should be an empty vect for a repository initialized."
+        draft.description,
+        Some("Draft of the next release".to_string())
     );
+    assert_eq!(draft.creator, Some("Marco Cella".to_string()));
+    assert_eq!(draft.base, Some("master".to_string()));
+    assert!(draft.created_at.is_some());
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitBranch {
+    name: String,
+    commit_uuid: Option<String>,
+    commit_time: Option<i64>,
+    description: Option<String>,
+    creator: Option<String>,
+    base: Option<String>,
+    created_at: Option<u64>,
 }
 
 fn get_workspace_name() -> String {
@@ -1,13 +1,13 @@
 use docversions::configuration::{get_configuration, Settings};
 use docversions::startup::run;
-use git2::{Commit, IndexAddOption, ObjectType, Repository, Signature};
+use git2::{BranchType, Commit, IndexAddOption, ObjectType, Oid, Repository, Signature};
 use std::fs;
 use std::net::TcpListener;
 use std::path::PathBuf;
 use uuid::Uuid;
 
 #[tokio::test]
-async fn retrieve_files_content_returns_500_for_initialized_repository() {
+async fn retrieve_files_content_returns_404_for_initialized_repository() {
     let workspace_name = get_workspace_name();
     let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
         panic!("Error while configuring the test: {:?}.", error);
@@ -32,7 +32,7 @@ async fn retrieve_files_content_returns_500_for_initialized_repository() {
         .expect("Failed to execute request.");
 
     cleanup(&configuration_file, &configuration, &workspace_name);
-    assert!(response.status().is_server_error());
+    assert_eq!(response.status().as_u16(), 404);
 }
 
 #[tokio::test]
@@ -120,6 +120,227 @@ async fn retrieve_file_content_on_master_branch_returns_file_content_for_reposit
     );
 }
 
+#[tokio::test]
+async fn retrieve_file_content_returns_400_on_reserved_file_name() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/api/workspaces/{}/branches/master/files/.git",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn retrieve_file_content_on_other_branch_leaves_head_and_working_tree_untouched() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let copy_file_result = copy_file(&configuration, &workspace_name);
+    assert!(copy_file_result.is_ok());
+    let second_commit_result = create_second_commit(&repository);
+    assert!(second_commit_result.is_ok());
+    let master_content = fs::read_to_string(workspace.join("README.md")).unwrap();
+    let last_commit = find_last_commit(&repository).unwrap();
+    assert!(repository.branch("draft", &last_commit, false).is_ok());
+    let draft_commit = create_branch_commit(&repository, "draft", "README.md", "draft\n");
+    assert!(draft_commit.is_ok());
+    fs::write(workspace.join("NOTES.md"), "untracked\n").unwrap();
+    let client = reqwest::Client::new();
+
+    let content = client
+        .get(format!(
+            "{}/api/workspaces/{}/branches/draft/files/README.md",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .text()
+        .await
+        .expect("failed to get payload");
+    let files = client
+        .get(format!(
+            "{}/api/workspaces/{}/branches/draft/files",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .text()
+        .await
+        .expect("failed to get payload");
+    let logs = client
+        .get(format!(
+            "{}/api/workspaces/{}/branches/draft/logs",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let head = repository.head().unwrap().name().map(|n| n.to_string());
+    let working_tree_content = fs::read_to_string(workspace.join("README.md")).unwrap();
+    let untracked_file_exists = workspace.join("NOTES.md").exists();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(content, "draft\n");
+    assert_eq!(files, "[{\"name\":\"README.md\",\"status\":\"current\"}]");
+    assert!(logs.status().is_success());
+    assert_eq!(head, Some("refs/heads/master".to_string()));
+    assert_eq!(working_tree_content, master_content);
+    assert!(untracked_file_exists);
+}
+
+#[tokio::test]
+async fn retrieve_file_content_reads_files_inside_directories() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let last_commit = find_last_commit(&repository).unwrap();
+    assert!(repository.branch("draft", &last_commit, false).is_ok());
+    let draft_commit = create_directory_commit(&repository, "draft", "chapters", "one.md", "one\n");
+    assert!(draft_commit.is_ok());
+    let client = reqwest::Client::new();
+
+    let files = client
+        .get(format!(
+            "{}/api/workspaces/{}/branches/draft/files",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .text()
+        .await
+        .expect("failed to get payload");
+    let content = client
+        .get(format!(
+            "{}/api/workspaces/{}/branches/draft/files/chapters/one.md",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .text()
+        .await
+        .expect("failed to get payload");
+    let missing_file = client
+        .get(format!(
+            "{}/api/workspaces/{}/branches/draft/files/chapters/two.md",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let reserved_name = client
+        .get(format!(
+            "{}/api/workspaces/{}/branches/draft/files/chapters/.git",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let missing_branch = client
+        .get(format!(
+            "{}/api/workspaces/{}/branches/other/files/chapters/one.md",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(
+        files,
+        "[{\"name\":\"chapters/one.md\",\"status\":\"current\"}]"
+    );
+    assert_eq!(content, "one\n");
+    assert_eq!(missing_file.status().as_u16(), 404);
+    assert_eq!(reserved_name.status().as_u16(), 400);
+    assert_eq!(missing_branch.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn retrieve_files_status_on_checked_out_branch_reports_working_tree_status() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let copy_file_result = copy_file(&configuration, &workspace_name);
+    assert!(copy_file_result.is_ok());
+    let second_commit_result = create_second_commit(&repository);
+    assert!(second_commit_result.is_ok());
+    fs::remove_file(workspace.join("README.md")).unwrap();
+    fs::write(workspace.join("NOTES.md"), "untracked\n").unwrap();
+    let client = reqwest::Client::new();
+
+    let files = client
+        .get(format!(
+            "{}/api/workspaces/{}/branches/master/files",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .text()
+        .await
+        .expect("failed to get payload");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(
+        files,
+        "[{\"name\":\"NOTES.md\",\"status\":\"new\"},{\"name\":\"README.md\",\"status\":\"deleted\"}]"
+    );
+}
+
 fn get_workspace_name() -> String {
     let uuid = Uuid::new_v4().to_simple_string();
     return uuid;
@@ -194,7 +415,7 @@ fn copy_file(configuration: &Settings, workspace_name: &String) -> std::io::Resu
     let mut file_path = PathBuf::from(&configuration.workspaces_path);
     file_path.push(workspace_name);
     file_path.push("README.md");
-    std::fs::copy("worspace_README.md", &file_path)
+    std::fs::copy("templates/default/README.md", &file_path)
 }
 
 fn create_second_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
@@ -221,3 +442,57 @@ fn find_last_commit(repo: &Repository) -> Result<Commit, git2::Error> {
     obj.into_commit()
         .map_err(|_| git2::Error::from_str("Couldn't find commit"))
 }
+
+fn create_branch_commit(
+    repository: &Repository,
+    branch_name: &str,
+    file_name: &str,
+    content: &str,
+) -> Result<Oid, git2::Error> {
+    let parent = repository
+        .find_branch(branch_name, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let blob = repository.blob(content.as_bytes())?;
+    let mut tree_builder = repository.treebuilder(Some(&parent.tree()?))?;
+    tree_builder.insert(file_name, blob, 0o100644)?;
+    let tree = repository.find_tree(tree_builder.write()?)?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    repository.commit(
+        Some(&format!("refs/heads/{}", branch_name)),
+        &signature,
+        &signature,
+        &format!("update {}", file_name),
+        &tree,
+        &[&parent],
+    )
+}
+
+fn create_directory_commit(
+    repository: &Repository,
+    branch_name: &str,
+    directory_name: &str,
+    file_name: &str,
+    content: &str,
+) -> Result<Oid, git2::Error> {
+    let parent = repository
+        .find_branch(branch_name, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let blob = repository.blob(content.as_bytes())?;
+    let mut directory_builder = repository.treebuilder(None)?;
+    directory_builder.insert(file_name, blob, 0o100644)?;
+    let directory = directory_builder.write()?;
+    let mut tree_builder = repository.treebuilder(Some(&parent.tree()?))?;
+    tree_builder.insert(directory_name, directory, 0o040000)?;
+    let tree = repository.find_tree(tree_builder.write()?)?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    repository.commit(
+        Some(&format!("refs/heads/{}", branch_name)),
+        &signature,
+        &signature,
+        &format!("update {}/{}", directory_name, file_name),
+        &tree,
+        &[&parent],
+    )
+}
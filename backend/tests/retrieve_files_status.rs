@@ -7,7 +7,7 @@ use std::path::PathBuf;
 use uuid::Uuid;
 
 #[tokio::test]
-async fn retrieve_files_status_returns_500_for_initialized_repository() {
+async fn retrieve_files_status_returns_404_for_initialized_repository() {
     let workspace_name = get_workspace_name();
     let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
         panic!("Error while configuring the test: {:?}.", error);
@@ -31,7 +31,7 @@ async fn retrieve_files_status_returns_500_for_initialized_repository() {
         .expect("Failed to execute request.");
 
     cleanup(&configuration_file, &configuration, &workspace_name);
-    assert!(response.status().is_server_error());
+    assert_eq!(response.status().as_u16(), 404);
 }
 
 #[tokio::test]
@@ -226,7 +226,7 @@ fn copy_file(configuration: &Settings, workspace_name: &String) -> std::io::Resu
     let mut file_path = PathBuf::from(&configuration.workspaces_path);
     file_path.push(workspace_name);
     file_path.push("README.md");
-    std::fs::copy("worspace_README.md", &file_path)
+    std::fs::copy("templates/default/README.md", &file_path)
 }
 
 fn create_second_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
@@ -0,0 +1,160 @@
+use docversions::configuration::{get_configuration, Settings};
+use docversions::startup::run;
+use git2::{Repository, Signature};
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn retrieve_workspace_usage_returns_404_on_non_existing_workspace() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name, "").unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/api/workspaces/{}/usage",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn retrieve_workspace_usage_returns_usage_and_limits() {
+    let workspace_name = get_workspace_name();
+    let quotas = format!(
+        "\nquotas:\n  max_repository_size: 1048576\n  max_files: 100\n  workspace_overrides:\n    - workspace: \"{}\"\n      max_files: 5\n      max_file_size: 1024\n",
+        &workspace_name
+    );
+    let configuration_file = configure_test(&workspace_name, &quotas).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    fs::write(workspace.join("README.md"), "# Workspace\n").expect("Failed to write the file.");
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/api/workspaces/{}/usage",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let status = response.status();
+    let usage: WorkspaceUsage = response.json().await.expect("failed to get payload");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert_eq!(usage.files, 1);
+    assert!(usage.repository_size > 12);
+    assert_eq!(usage.limits.max_repository_size, Some(1048576));
+    assert_eq!(usage.limits.max_file_size, Some(1024));
+    assert_eq!(usage.limits.max_files, Some(5));
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct WorkspaceUsage {
+    repository_size: u64,
+    files: u64,
+    limits: QuotaLimits,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct QuotaLimits {
+    max_repository_size: Option<u64>,
+    max_file_size: Option<u64>,
+    max_files: Option<u64>,
+}
+
+fn get_workspace_name() -> String {
+    Uuid::new_v4().to_simple_string()
+}
+
+fn configure_test(workspace_name: &str, extra_configuration: &str) -> std::io::Result<PathBuf> {
+    let mut configuration_file = PathBuf::from("tests_execution");
+    configuration_file.push(workspace_name);
+    configuration_file.set_extension("yaml");
+    let mut configuration = fs::read_to_string("configuration_test.yaml")?;
+    configuration.push_str(extra_configuration);
+    fs::write(&configuration_file, configuration)?;
+    Ok(configuration_file)
+}
+fn cleanup(configuration_file: &Path, configuration: &Settings, workspace_name: &str) {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace.as_path());
+    }
+    if configuration_file.exists() {
+        _ = fs::remove_file(configuration_file);
+    }
+}
+
+fn spawn_app(configuration: &Settings, workspace: &Path) -> String {
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace);
+    }
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let server = run(listener, configuration).expect("Failed to bind address");
+    tokio::spawn(server);
+    format!("http://127.0.0.1:{}", port)
+}
+
+fn get_workspace(configuration: &Settings, workspace_name: &str) -> PathBuf {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    workspace
+}
+
+fn create_git_repository(workspace: &Path) -> Result<Repository, git2::Error> {
+    Repository::init(workspace)
+}
+
+fn create_master_branch(repository: &Repository) -> Result<(), git2::Error> {
+    create_first_commit(repository)?;
+    let (object, reference) = repository.revparse_ext("master")?;
+    match reference {
+        Some(gref) => repository.set_head(gref.name().unwrap())?,
+        None => repository.set_head_detached(object.id())?,
+    };
+    Ok(())
+}
+
+fn create_first_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
+    let mut index = repository.index()?;
+    let oid = index.write_tree()?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+
+    let tree = repository.find_tree(oid)?;
+
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "commit message",
+        &tree,
+        &[],
+    )
+}
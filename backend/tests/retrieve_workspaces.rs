@@ -104,23 +104,253 @@ async fn retrieve_workspace_returns_vector_with_one_element_on_one_workspace() {
     assert!(branch_result.is_ok());
     let client = reqwest::Client::new();
 
-    let response = client
+    let response: Vec<Workspace> = client
         .get(&format!("{}/api/workspaces", &address))
         .send()
         .await
         .expect("Failed to execute request.")
-        .text()
+        .json()
         .await
         .expect("failed to get payload");
 
     cleanup(&configuration_file, &configuration, &workspace_name);
+    let workspace_names: Vec<String> = response.into_iter().map(|w| w.name).collect();
     assert_eq!(
-        response,
-        format!("[\"{}\"]", &workspace_name),
+        workspace_names,
+        vec![workspace_name],
         "The body content should be a vector with one workspace"
     );
 }
 
+#[tokio::test]
+#[serial]
+async fn retrieve_workspace_returns_workspaces_filtered_by_label() {
+    let workspace_name = get_workspace_name();
+    let other_workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let address = spawn_app(&configuration);
+    let client = reqwest::Client::new();
+    let metadata = WorkspaceMetadata {
+        description: "policy manual".to_string(),
+        owner: "legal".to_string(),
+        labels: vec!["legal".to_string(), "2023".to_string()],
+    };
+    client
+        .post(format!("{}/api/workspaces/{}", &address, &workspace_name))
+        .json(&metadata)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    client
+        .post(format!(
+            "{}/api/workspaces/{}",
+            &address, &other_workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let response: Vec<Workspace> = client
+        .get(format!("{}/api/workspaces?label=legal", &address))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json()
+        .await
+        .expect("failed to get payload");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    cleanup(&configuration_file, &configuration, &other_workspace_name);
+    assert_eq!(response.len(), 1);
+    assert_eq!(response[0].name, workspace_name);
+    assert_eq!(response[0].description, Some(metadata.description));
+    assert_eq!(response[0].owner, Some(metadata.owner));
+    assert_eq!(response[0].labels, metadata.labels);
+    assert!(response[0].created_at.is_some());
+}
+
+#[tokio::test]
+#[serial]
+async fn retrieve_workspace_returns_workspace_statistics() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let address = spawn_app(&configuration);
+    let client = reqwest::Client::new();
+    client
+        .post(format!("{}/api/workspaces/{}", &address, &workspace_name))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let response: Vec<Workspace> = client
+        .get(format!(
+            "{}/api/workspaces?prefix={}",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json()
+        .await
+        .expect("failed to get payload");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.len(), 1);
+    assert_eq!(response[0].branch_count, 1);
+    let last_commit = response[0]
+        .last_commit
+        .as_ref()
+        .expect("The workspace should have a last commit");
+    assert_eq!(last_commit.author, "Marco Cella");
+    assert_eq!(last_commit.message, "Initial commit");
+    assert!(response[0].size > 0);
+}
+
+#[tokio::test]
+#[serial]
+async fn retrieve_workspace_returns_workspace_statistics_on_every_sort() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let address = spawn_app(&configuration);
+    let client = reqwest::Client::new();
+    client
+        .post(format!("{}/api/workspaces/{}", &address, &workspace_name))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let mut responses = Vec::new();
+    for sort in ["name", "last_commit", "size", "created"] {
+        let response: Vec<Workspace> = client
+            .get(format!(
+                "{}/api/workspaces?prefix={}&sort={}&limit=1",
+                &address, &workspace_name, sort
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json()
+            .await
+            .expect("failed to get payload");
+        responses.push(response);
+    }
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    for response in responses {
+        assert_eq!(response.len(), 1);
+        assert_eq!(response[0].branch_count, 1);
+        assert!(response[0].last_commit.is_some());
+        assert!(response[0].created_at.is_some());
+        assert!(response[0].size > 0);
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn retrieve_workspace_returns_sorted_pages_with_cursor() {
+    let prefix = get_workspace_name();
+    let workspace_names: Vec<String> = ["a", "b", "c"]
+        .iter()
+        .map(|suffix| format!("{}-{}", prefix, suffix))
+        .collect();
+    let configuration_file = configure_test(&prefix).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let address = spawn_app(&configuration);
+    let client = reqwest::Client::new();
+    for workspace_name in &workspace_names {
+        client
+            .post(format!("{}/api/workspaces/{}", &address, workspace_name))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+    }
+
+    let first_page = client
+        .get(format!(
+            "{}/api/workspaces?prefix={}&sort=name&order=desc&limit=2",
+            &address, &prefix
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let cursor = first_page
+        .headers()
+        .get("X-Next-Cursor")
+        .map(|c| c.to_str().unwrap().to_string());
+    let first_page: Vec<Workspace> = first_page.json().await.expect("failed to get payload");
+    let second_page = client
+        .get(format!(
+            "{}/api/workspaces?prefix={}&sort=name&order=desc&limit=2&cursor={}",
+            &address,
+            &prefix,
+            cursor.clone().unwrap_or_default()
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let second_page_cursor = second_page.headers().get("X-Next-Cursor").cloned();
+    let second_page: Vec<Workspace> = second_page.json().await.expect("failed to get payload");
+    let invalid_cursor = client
+        .get(format!("{}/api/workspaces?cursor={}-z", &address, &prefix))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    for workspace_name in &workspace_names {
+        cleanup(&configuration_file, &configuration, workspace_name);
+    }
+    let first_page: Vec<String> = first_page.into_iter().map(|w| w.name).collect();
+    let second_page: Vec<String> = second_page.into_iter().map(|w| w.name).collect();
+    assert_eq!(
+        first_page,
+        vec![workspace_names[2].clone(), workspace_names[1].clone()]
+    );
+    assert_eq!(cursor, Some(workspace_names[1].clone()));
+    assert_eq!(second_page, vec![workspace_names[0].clone()]);
+    assert!(second_page_cursor.is_none());
+    assert_eq!(invalid_cursor.status().as_u16(), 400);
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Workspace {
+    name: String,
+    description: Option<String>,
+    owner: Option<String>,
+    labels: Vec<String>,
+    created_at: Option<i64>,
+    branch_count: usize,
+    last_commit: Option<WorkspaceCommit>,
+    size: u64,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct WorkspaceCommit {
+    author: String,
+    message: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct WorkspaceMetadata {
+    description: String,
+    owner: String,
+    labels: Vec<String>,
+}
+
 fn get_workspace_name() -> String {
     let uuid = Uuid::new_v4().to_simple_string();
     return uuid;
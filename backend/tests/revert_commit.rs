@@ -0,0 +1,388 @@
+use docversions::configuration::{get_configuration, Settings};
+use docversions::startup::run;
+use git2::{BranchType, Commit, ObjectType, Oid, Repository, Signature};
+use std::collections::HashMap;
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn revert_commit_creates_inverse_commit() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let _first_commit = create_branch_commit(&repository, "master", "README.md", "first\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let second_commit = create_branch_commit(&repository, "master", "README.md", "second\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+
+    let response = revert_commit(
+        &client,
+        &address,
+        &workspace_name,
+        "master",
+        &second_commit.to_string(),
+    )
+    .await;
+    let status = response.status();
+    let revert_result: RevertResult = response.json().await.expect("failed to get payload");
+
+    let revert_commit = repository
+        .find_commit(Oid::from_str(&revert_result.commit_uuid).unwrap())
+        .unwrap_or_else(|e| {
+            panic!("Error while retrieving the revert commit: {:?}", e);
+        });
+    let parents: Vec<Oid> = revert_commit.parent_ids().collect();
+    let message = revert_commit.message().unwrap_or_default().to_string();
+    let master_tip = get_branch_tip(&repository, "master");
+    let readme = get_branch_file_content(&repository, "master", "README.md");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert_eq!(
+        revert_result.reverted_commit_uuid,
+        second_commit.to_string()
+    );
+    assert_eq!(parents, vec![second_commit]);
+    assert_eq!(master_tip, Some(revert_commit.id()));
+    assert_eq!(readme, "first\n");
+    assert!(message.contains(&second_commit.to_string()));
+}
+
+#[tokio::test]
+async fn revert_commit_returns_409_with_conflicted_files() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let _first_commit = create_branch_commit(&repository, "master", "README.md", "first\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let second_commit = create_branch_commit(&repository, "master", "README.md", "second\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let third_commit = create_branch_commit(&repository, "master", "README.md", "third\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+
+    let response = revert_commit(
+        &client,
+        &address,
+        &workspace_name,
+        "master",
+        &second_commit.to_string(),
+    )
+    .await;
+    let status = response.status();
+    let revert_conflicts: RevertConflicts = response.json().await.expect("failed to get payload");
+
+    let master_tip = get_branch_tip(&repository, "master");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(status.as_u16(), 409);
+    assert_eq!(revert_conflicts.conflicts.len(), 1);
+    assert_eq!(revert_conflicts.conflicts[0].path, "README.md");
+    assert_eq!(revert_conflicts.conflicts[0].kind, "both_modified");
+    assert_eq!(master_tip, Some(third_commit));
+}
+
+#[tokio::test]
+async fn revert_commit_returns_400_on_commit_outside_of_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    assert!(create_branch(&repository, "draft").is_ok());
+    let draft_commit = create_branch_commit(&repository, "draft", "README.md", "draft\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+
+    let response = revert_commit(
+        &client,
+        &address,
+        &workspace_name,
+        "master",
+        &draft_commit.to_string(),
+    )
+    .await;
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn revert_commit_returns_404_on_non_existing_commit() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = revert_commit(
+        &client,
+        &address,
+        &workspace_name,
+        "master",
+        "0123456789012345678901234567890123456789",
+    )
+    .await;
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn revert_commit_returns_403_on_protected_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let first_commit = create_branch_commit(&repository, "master", "README.md", "first\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let protect_result = repository
+        .config()
+        .and_then(|mut c| c.set_bool("branch.master.nodirectcommits", true));
+    assert!(protect_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = revert_commit(
+        &client,
+        &address,
+        &workspace_name,
+        "master",
+        &first_commit.to_string(),
+    )
+    .await;
+
+    let master_tip = get_branch_tip(&repository, "master");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 403);
+    assert_eq!(master_tip, Some(first_commit));
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct RevertResult {
+    commit_uuid: String,
+    reverted_commit_uuid: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct RevertConflicts {
+    conflicts: Vec<MergeConflict>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct MergeConflict {
+    path: String,
+    kind: String,
+}
+
+fn get_workspace_name() -> String {
+    Uuid::new_v4().to_simple_string()
+}
+
+fn configure_test(workspace_name: &str) -> std::io::Result<PathBuf> {
+    let mut configuration_file = PathBuf::from("tests_execution");
+    configuration_file.push(workspace_name);
+    configuration_file.set_extension("yaml");
+    std::fs::copy("configuration_test.yaml", &configuration_file)?;
+    Ok(configuration_file)
+}
+
+fn cleanup(configuration_file: &Path, configuration: &Settings, workspace_name: &str) {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace.as_path());
+    }
+    if configuration_file.exists() {
+        _ = fs::remove_file(configuration_file);
+    }
+}
+
+fn spawn_app(configuration: &Settings, workspace: &Path) -> String {
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace);
+    }
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let server = run(listener, configuration).expect("Failed to bind address");
+    tokio::spawn(server);
+    format!("http://127.0.0.1:{}", port)
+}
+
+fn get_workspace(configuration: &Settings, workspace_name: &str) -> PathBuf {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    workspace
+}
+
+fn create_git_repository(workspace: &Path) -> Result<Repository, git2::Error> {
+    Repository::init(workspace)
+}
+
+fn create_master_branch(repository: &Repository) -> Result<(), git2::Error> {
+    create_first_commit(repository)?;
+    let (object, reference) = repository.revparse_ext("master")?;
+    match reference {
+        Some(gref) => repository.set_head(gref.name().unwrap())?,
+        None => repository.set_head_detached(object.id())?,
+    };
+    Ok(())
+}
+
+fn create_first_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
+    let mut index = repository.index()?;
+    let oid = index.write_tree()?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+
+    let tree = repository.find_tree(oid)?;
+
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "commit message",
+        &tree,
+        &[],
+    )
+}
+
+fn find_last_commit(repo: &Repository) -> Result<Commit<'_>, git2::Error> {
+    let obj = repo.head()?.resolve()?.peel(ObjectType::Commit)?;
+    obj.into_commit()
+        .map_err(|_| git2::Error::from_str("Couldn't find commit"))
+}
+
+fn create_branch(repository: &Repository, branch_name: &str) -> Result<(), git2::Error> {
+    let last_commit = find_last_commit(repository)?;
+    repository.branch(branch_name, &last_commit, false)?;
+    Ok(())
+}
+
+fn create_branch_commit(
+    repository: &Repository,
+    branch_name: &str,
+    file_name: &str,
+    content: &str,
+) -> Result<Oid, git2::Error> {
+    let parent = repository
+        .find_branch(branch_name, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let blob = repository.blob(content.as_bytes())?;
+    let mut tree_builder = repository.treebuilder(Some(&parent.tree()?))?;
+    tree_builder.insert(file_name, blob, 0o100644)?;
+    let tree = repository.find_tree(tree_builder.write()?)?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    repository.commit(
+        Some(&format!("refs/heads/{}", branch_name)),
+        &signature,
+        &signature,
+        &format!("update {}", file_name),
+        &tree,
+        &[&parent],
+    )
+}
+
+fn get_branch_tip(repository: &Repository, branch_name: &str) -> Option<Oid> {
+    repository
+        .find_branch(branch_name, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .map(|c| c.id())
+        .ok()
+}
+
+fn get_branch_file_content(repository: &Repository, branch_name: &str, file_name: &str) -> String {
+    let commit = repository
+        .find_branch(branch_name, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .unwrap_or_else(|e| {
+            panic!("Error while retrieving the branch: {:?}", e);
+        });
+    let blob = commit
+        .tree()
+        .and_then(|t| t.get_path(Path::new(file_name)))
+        .and_then(|e| e.to_object(repository))
+        .and_then(|o| o.peel_to_blob())
+        .unwrap_or_else(|e| {
+            panic!("Error while retrieving the file: {:?}", e);
+        });
+    String::from_utf8_lossy(blob.content()).to_string()
+}
+
+async fn revert_commit(
+    client: &reqwest::Client,
+    address: &str,
+    workspace_name: &str,
+    branch_name: &str,
+    commit_uuid: &str,
+) -> reqwest::Response {
+    client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/{}/revert",
+            address, workspace_name, branch_name
+        ))
+        .json(&HashMap::from([("commit_uuid", commit_uuid)]))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
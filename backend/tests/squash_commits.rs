@@ -0,0 +1,414 @@
+use docversions::configuration::{get_configuration, Settings};
+use docversions::startup::run;
+use git2::{BranchType, Commit, ObjectType, Oid, Repository, Signature};
+use std::collections::HashMap;
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn squash_commits_squashes_range_up_to_tip() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let initial_commit = get_branch_tip(&repository, "master").unwrap();
+    let first_commit = create_branch_commit(&repository, "master", "README.md", "first\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let _second_commit = create_branch_commit(&repository, "master", "NOTES.md", "second\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let third_commit = create_branch_commit(&repository, "master", "README.md", "third\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+
+    let response = squash_commits(
+        &client,
+        &address,
+        &workspace_name,
+        "master",
+        HashMap::from([
+            ("from", first_commit.to_string()),
+            ("message", "squashed".to_string()),
+        ]),
+    )
+    .await;
+    let status = response.status();
+    let squash_result: SquashResult = response.json().await.expect("failed to get payload");
+
+    let master_tip = get_branch_tip(&repository, "master").unwrap();
+    let squash_commit = repository.find_commit(master_tip).unwrap_or_else(|e| {
+        panic!("Error while retrieving the commit: {:?}", e);
+    });
+    let parents: Vec<Oid> = squash_commit.parent_ids().collect();
+    let message = squash_commit.message().unwrap_or_default().to_string();
+    let readme = get_branch_file_content(&repository, "master", "README.md");
+    let notes = get_branch_file_content(&repository, "master", "NOTES.md");
+    let backup_tip = repository
+        .find_reference(&format!("refs/backups/master/{}", &squash_result.backup.id))
+        .and_then(|r| r.peel_to_commit())
+        .map(|c| c.id())
+        .ok();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert_eq!(squash_result.commit_uuid, master_tip.to_string());
+    assert_eq!(squash_result.squash_commit_uuid, master_tip.to_string());
+    assert_eq!(squash_result.squashed_commits.len(), 3);
+    assert_eq!(parents, vec![initial_commit]);
+    assert_eq!(message, "squashed");
+    assert_eq!(readme, "third\n");
+    assert_eq!(notes, "second\n");
+    assert_eq!(backup_tip, Some(third_commit));
+}
+
+#[tokio::test]
+async fn squash_commits_replays_following_commits() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let first_commit = create_branch_commit(&repository, "master", "README.md", "first\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let second_commit = create_branch_commit(&repository, "master", "README.md", "second\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let third_commit = create_branch_commit(&repository, "master", "README.md", "third\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let _fourth_commit = create_branch_commit(&repository, "master", "NOTES.md", "fourth\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+
+    let response = squash_commits(
+        &client,
+        &address,
+        &workspace_name,
+        "master",
+        HashMap::from([
+            ("from", second_commit.to_string()),
+            ("to", third_commit.to_string()),
+            ("message", "squashed".to_string()),
+        ]),
+    )
+    .await;
+    let status = response.status();
+    let squash_result: SquashResult = response.json().await.expect("failed to get payload");
+
+    let master_tip = get_branch_tip(&repository, "master").unwrap();
+    let tip_commit = repository.find_commit(master_tip).unwrap_or_else(|e| {
+        panic!("Error while retrieving the commit: {:?}", e);
+    });
+    let tip_message = tip_commit.message().unwrap_or_default().to_string();
+    let squash_commit = repository
+        .find_commit(tip_commit.parent_id(0).unwrap())
+        .unwrap_or_else(|e| {
+            panic!("Error while retrieving the commit: {:?}", e);
+        });
+    let squash_parents: Vec<Oid> = squash_commit.parent_ids().collect();
+    let readme = get_branch_file_content(&repository, "master", "README.md");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert_eq!(squash_result.commit_uuid, master_tip.to_string());
+    assert_eq!(
+        squash_result.squash_commit_uuid,
+        squash_commit.id().to_string()
+    );
+    assert_eq!(
+        squash_result.squashed_commits,
+        vec![second_commit.to_string(), third_commit.to_string()]
+    );
+    assert_eq!(tip_message, "update NOTES.md");
+    assert_eq!(squash_parents, vec![first_commit]);
+    assert_eq!(readme, "third\n");
+}
+
+#[tokio::test]
+async fn squash_commits_returns_400_on_commit_outside_of_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    assert!(create_branch(&repository, "draft").is_ok());
+    let draft_commit = create_branch_commit(&repository, "draft", "README.md", "draft\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let master_commit = create_branch_commit(&repository, "master", "README.md", "master\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+
+    let response = squash_commits(
+        &client,
+        &address,
+        &workspace_name,
+        "master",
+        HashMap::from([
+            ("from", draft_commit.to_string()),
+            ("message", "squashed".to_string()),
+        ]),
+    )
+    .await;
+
+    let master_tip = get_branch_tip(&repository, "master");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(master_tip, Some(master_commit));
+}
+
+#[tokio::test]
+async fn squash_commits_returns_403_on_protected_branch() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let first_commit = create_branch_commit(&repository, "master", "README.md", "first\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let second_commit = create_branch_commit(&repository, "master", "README.md", "second\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let protect_result = repository
+        .config()
+        .and_then(|mut c| c.set_bool("branch.master.nodirectcommits", true));
+    assert!(protect_result.is_ok());
+    let client = reqwest::Client::new();
+
+    let response = squash_commits(
+        &client,
+        &address,
+        &workspace_name,
+        "master",
+        HashMap::from([
+            ("from", first_commit.to_string()),
+            ("message", "squashed".to_string()),
+        ]),
+    )
+    .await;
+
+    let master_tip = get_branch_tip(&repository, "master");
+    let backups = repository
+        .references_glob("refs/backups/*")
+        .map(|r| r.count())
+        .unwrap_or_default();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 403);
+    assert_eq!(master_tip, Some(second_commit));
+    assert_eq!(backups, 0);
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct SquashResult {
+    commit_uuid: String,
+    squash_commit_uuid: String,
+    squashed_commits: Vec<String>,
+    backup: BranchBackup,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct BranchBackup {
+    id: String,
+}
+
+fn get_workspace_name() -> String {
+    Uuid::new_v4().to_simple_string()
+}
+
+fn configure_test(workspace_name: &str) -> std::io::Result<PathBuf> {
+    let mut configuration_file = PathBuf::from("tests_execution");
+    configuration_file.push(workspace_name);
+    configuration_file.set_extension("yaml");
+    std::fs::copy("configuration_test.yaml", &configuration_file)?;
+    Ok(configuration_file)
+}
+
+fn cleanup(configuration_file: &Path, configuration: &Settings, workspace_name: &str) {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace.as_path());
+    }
+    if configuration_file.exists() {
+        _ = fs::remove_file(configuration_file);
+    }
+}
+
+fn spawn_app(configuration: &Settings, workspace: &Path) -> String {
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace);
+    }
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let server = run(listener, configuration).expect("Failed to bind address");
+    tokio::spawn(server);
+    format!("http://127.0.0.1:{}", port)
+}
+
+fn get_workspace(configuration: &Settings, workspace_name: &str) -> PathBuf {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    workspace
+}
+
+fn create_git_repository(workspace: &Path) -> Result<Repository, git2::Error> {
+    Repository::init(workspace)
+}
+
+fn create_master_branch(repository: &Repository) -> Result<(), git2::Error> {
+    create_first_commit(repository)?;
+    let (object, reference) = repository.revparse_ext("master")?;
+    match reference {
+        Some(gref) => repository.set_head(gref.name().unwrap())?,
+        None => repository.set_head_detached(object.id())?,
+    };
+    Ok(())
+}
+
+fn create_first_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
+    let mut index = repository.index()?;
+    let oid = index.write_tree()?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+
+    let tree = repository.find_tree(oid)?;
+
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "commit message",
+        &tree,
+        &[],
+    )
+}
+
+fn find_last_commit(repo: &Repository) -> Result<Commit<'_>, git2::Error> {
+    let obj = repo.head()?.resolve()?.peel(ObjectType::Commit)?;
+    obj.into_commit()
+        .map_err(|_| git2::Error::from_str("Couldn't find commit"))
+}
+
+fn create_branch(repository: &Repository, branch_name: &str) -> Result<(), git2::Error> {
+    let last_commit = find_last_commit(repository)?;
+    repository.branch(branch_name, &last_commit, false)?;
+    Ok(())
+}
+
+fn create_branch_commit(
+    repository: &Repository,
+    branch_name: &str,
+    file_name: &str,
+    content: &str,
+) -> Result<Oid, git2::Error> {
+    let parent = repository
+        .find_branch(branch_name, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let blob = repository.blob(content.as_bytes())?;
+    let mut tree_builder = repository.treebuilder(Some(&parent.tree()?))?;
+    tree_builder.insert(file_name, blob, 0o100644)?;
+    let tree = repository.find_tree(tree_builder.write()?)?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    repository.commit(
+        Some(&format!("refs/heads/{}", branch_name)),
+        &signature,
+        &signature,
+        &format!("update {}", file_name),
+        &tree,
+        &[&parent],
+    )
+}
+
+fn get_branch_tip(repository: &Repository, branch_name: &str) -> Option<Oid> {
+    repository
+        .find_branch(branch_name, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .map(|c| c.id())
+        .ok()
+}
+
+fn get_branch_file_content(repository: &Repository, branch_name: &str, file_name: &str) -> String {
+    let commit = repository
+        .find_branch(branch_name, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .unwrap_or_else(|e| {
+            panic!("Error while retrieving the branch: {:?}", e);
+        });
+    let blob = commit
+        .tree()
+        .and_then(|t| t.get_path(Path::new(file_name)))
+        .and_then(|e| e.to_object(repository))
+        .and_then(|o| o.peel_to_blob())
+        .unwrap_or_else(|e| {
+            panic!("Error while retrieving the file: {:?}", e);
+        });
+    String::from_utf8_lossy(blob.content()).to_string()
+}
+
+async fn squash_commits(
+    client: &reqwest::Client,
+    address: &str,
+    workspace_name: &str,
+    branch_name: &str,
+    body: HashMap<&str, String>,
+) -> reqwest::Response {
+    client
+        .post(format!(
+            "{}/api/workspaces/{}/branches/{}/squash",
+            address, workspace_name, branch_name
+        ))
+        .json(&body)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
@@ -0,0 +1,428 @@
+use docversions::configuration::{get_configuration, Settings};
+use docversions::startup::run;
+use git2::{BranchType, Oid, Repository, Signature};
+use reqwest::multipart::Form;
+use std::collections::HashMap;
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn create_tag_tags_default_branch_and_lists_it() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let first_commit = create_branch_commit(&repository, "master", "README.md", "first\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+
+    let response = create_tag(&client, &address, &workspace_name, "v1", None).await;
+    let status = response.status();
+    let tag: GitTag = response.json().await.expect("failed to get payload");
+    let tags: Vec<GitTag> = client
+        .get(format!(
+            "{}/api/workspaces/{}/tags",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json()
+        .await
+        .expect("failed to get payload");
+
+    let is_annotated = repository
+        .find_reference("refs/tags/v1")
+        .and_then(|r| r.peel_to_tag())
+        .is_ok();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert_eq!(tag.name, "v1");
+    assert_eq!(tag.commit_uuid, first_commit.to_string());
+    assert_eq!(tag.message, Some("First edition".to_string()));
+    assert!(is_annotated);
+    assert_eq!(tags.len(), 1);
+    assert_eq!(tags[0].name, "v1");
+    assert_eq!(tags[0].commit_uuid, first_commit.to_string());
+}
+
+#[tokio::test]
+async fn create_tag_tags_commit_id() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let initial_commit = get_branch_tip(&repository, "master").unwrap();
+    create_branch_commit(&repository, "master", "README.md", "first\n").unwrap_or_else(|e| {
+        panic!("Error while creating the commit: {:?}", e);
+    });
+    let client = reqwest::Client::new();
+
+    let response = create_tag(
+        &client,
+        &address,
+        &workspace_name,
+        "v0",
+        Some(&initial_commit.to_string()),
+    )
+    .await;
+    let status = response.status();
+    let tag: GitTag = response.json().await.expect("failed to get payload");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(status.is_success());
+    assert_eq!(tag.commit_uuid, initial_commit.to_string());
+}
+
+#[tokio::test]
+async fn create_tag_returns_409_on_existing_tag_and_404_on_unknown_target() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+    let first_response = create_tag(&client, &address, &workspace_name, "v1", None).await;
+
+    let existing_response = create_tag(&client, &address, &workspace_name, "v1", None).await;
+    let unknown_response =
+        create_tag(&client, &address, &workspace_name, "v2", Some("draft")).await;
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(first_response.status().is_success());
+    assert_eq!(existing_response.status().as_u16(), 409);
+    assert_eq!(unknown_response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn delete_tag_removes_tag() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+    let create_response = create_tag(&client, &address, &workspace_name, "v1", None).await;
+
+    let response = client
+        .delete(format!(
+            "{}/api/workspaces/{}/tags/v1",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let missing_response = client
+        .delete(format!(
+            "{}/api/workspaces/{}/tags/v1",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let tag_exists = repository.find_reference("refs/tags/v1").is_ok();
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(create_response.status().is_success());
+    assert!(response.status().is_success());
+    assert_eq!(missing_response.status().as_u16(), 404);
+    assert!(!tag_exists);
+}
+
+#[tokio::test]
+async fn read_endpoints_accept_tag_name() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let first_commit = create_branch_commit(&repository, "master", "README.md", "first\n")
+        .unwrap_or_else(|e| {
+            panic!("Error while creating the commit: {:?}", e);
+        });
+    let client = reqwest::Client::new();
+    let tag_response = create_tag(&client, &address, &workspace_name, "v1", None).await;
+    create_branch_commit(&repository, "master", "README.md", "second\n").unwrap_or_else(|e| {
+        panic!("Error while creating the commit: {:?}", e);
+    });
+
+    let tag_content = client
+        .get(format!(
+            "{}/api/workspaces/{}/branches/v1/files/README.md",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .text()
+        .await
+        .expect("failed to get payload");
+    let tag_logs: Vec<GitLog> = client
+        .get(format!(
+            "{}/api/workspaces/{}/branches/v1/logs",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json()
+        .await
+        .expect("failed to get payload");
+    let tag_files = client
+        .get(format!(
+            "{}/api/workspaces/{}/branches/v1/files",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let master_content = client
+        .get(format!(
+            "{}/api/workspaces/{}/branches/master/files/README.md",
+            &address, &workspace_name
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .text()
+        .await
+        .expect("failed to get payload");
+
+    let tag_tip = get_tag_tip(&repository, "v1");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(tag_response.status().is_success());
+    assert_eq!(tag_content, "first\n");
+    assert_eq!(tag_logs.len(), 2);
+    assert!(tag_files.status().is_success());
+    assert_eq!(master_content, "second\n");
+    assert_eq!(tag_tip, Some(first_commit));
+}
+
+#[tokio::test]
+async fn file_changes_on_tag_name_are_refused() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    create_branch_commit(&repository, "master", "README.md", "first\n").unwrap_or_else(|e| {
+        panic!("Error while creating the commit: {:?}", e);
+    });
+    let client = reqwest::Client::new();
+    let tag_response = create_tag(&client, &address, &workspace_name, "v1", None).await;
+    let tag_tip = get_tag_tip(&repository, "v1");
+
+    let response = client
+        .delete(format!(
+            "{}/api/workspaces/{}/branches/v1/files/README.md",
+            &address, &workspace_name
+        ))
+        .multipart(Form::new().text("commit_message", "commit message"))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let new_tag_tip = get_tag_tip(&repository, "v1");
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(tag_response.status().is_success());
+    assert!(!response.status().is_success());
+    assert_eq!(new_tag_tip, tag_tip);
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitTag {
+    name: String,
+    commit_uuid: String,
+    message: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLog {}
+
+fn get_workspace_name() -> String {
+    Uuid::new_v4().to_simple_string()
+}
+
+fn configure_test(workspace_name: &str) -> std::io::Result<PathBuf> {
+    let mut configuration_file = PathBuf::from("tests_execution");
+    configuration_file.push(workspace_name);
+    configuration_file.set_extension("yaml");
+    std::fs::copy("configuration_test.yaml", &configuration_file)?;
+    Ok(configuration_file)
+}
+
+fn cleanup(configuration_file: &Path, configuration: &Settings, workspace_name: &str) {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace.as_path());
+    }
+    if configuration_file.exists() {
+        _ = fs::remove_file(configuration_file);
+    }
+}
+
+fn spawn_app(configuration: &Settings, workspace: &Path) -> String {
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace);
+    }
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let server = run(listener, configuration).expect("Failed to bind address");
+    tokio::spawn(server);
+    format!("http://127.0.0.1:{}", port)
+}
+
+fn get_workspace(configuration: &Settings, workspace_name: &str) -> PathBuf {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    workspace
+}
+
+fn create_git_repository(workspace: &Path) -> Result<Repository, git2::Error> {
+    Repository::init(workspace)
+}
+
+fn create_master_branch(repository: &Repository) -> Result<(), git2::Error> {
+    create_first_commit(repository)?;
+    let (object, reference) = repository.revparse_ext("master")?;
+    match reference {
+        Some(gref) => repository.set_head(gref.name().unwrap())?,
+        None => repository.set_head_detached(object.id())?,
+    };
+    Ok(())
+}
+
+fn create_first_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
+    let mut index = repository.index()?;
+    let oid = index.write_tree()?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+
+    let tree = repository.find_tree(oid)?;
+
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "commit message",
+        &tree,
+        &[],
+    )
+}
+
+fn create_branch_commit(
+    repository: &Repository,
+    branch_name: &str,
+    file_name: &str,
+    content: &str,
+) -> Result<Oid, git2::Error> {
+    let parent = repository
+        .find_branch(branch_name, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let blob = repository.blob(content.as_bytes())?;
+    let mut tree_builder = repository.treebuilder(Some(&parent.tree()?))?;
+    tree_builder.insert(file_name, blob, 0o100644)?;
+    let tree = repository.find_tree(tree_builder.write()?)?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+    repository.commit(
+        Some(&format!("refs/heads/{}", branch_name)),
+        &signature,
+        &signature,
+        &format!("update {}", file_name),
+        &tree,
+        &[&parent],
+    )
+}
+
+fn get_branch_tip(repository: &Repository, branch_name: &str) -> Option<Oid> {
+    repository
+        .find_branch(branch_name, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .map(|c| c.id())
+        .ok()
+}
+
+fn get_tag_tip(repository: &Repository, tag_name: &str) -> Option<Oid> {
+    repository
+        .find_reference(&format!("refs/tags/{}", tag_name))
+        .and_then(|r| r.peel_to_commit())
+        .map(|c| c.id())
+        .ok()
+}
+
+async fn create_tag(
+    client: &reqwest::Client,
+    address: &str,
+    workspace_name: &str,
+    tag_name: &str,
+    target: Option<&str>,
+) -> reqwest::Response {
+    let mut body = HashMap::from([("message", "First edition")]);
+    if let Some(target) = target {
+        body.insert("target", target);
+    }
+    client
+        .post(format!(
+            "{}/api/workspaces/{}/tags/{}",
+            address, workspace_name, tag_name
+        ))
+        .json(&body)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
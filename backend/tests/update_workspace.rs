@@ -0,0 +1,203 @@
+use docversions::configuration::{get_configuration, Settings};
+use docversions::startup::run;
+use git2::{Repository, Signature};
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn update_workspace_returns_200_on_existing_workspace() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let repository = create_git_repository(&workspace).unwrap_or_else(|e| {
+        panic!("Error while retrieving the repository: {:?}", e);
+    });
+    let branch_result = create_master_branch(&repository);
+    assert!(branch_result.is_ok());
+    let client = reqwest::Client::new();
+    let metadata = WorkspaceMetadata {
+        description: Some("policy manual".to_string()),
+        owner: Some("legal".to_string()),
+        labels: Some(vec!["legal".to_string()]),
+    };
+
+    let response = client
+        .patch(format!("{}/api/workspaces/{}", &address, &workspace_name))
+        .json(&metadata)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert!(response.status().is_success());
+}
+
+#[tokio::test]
+async fn update_workspace_returns_404_on_non_existing_workspace() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let client = reqwest::Client::new();
+    let metadata = WorkspaceMetadata {
+        description: Some("policy manual".to_string()),
+        owner: None,
+        labels: None,
+    };
+
+    let response = client
+        .patch(format!("{}/api/workspaces/{}", &address, &workspace_name))
+        .json(&metadata)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn update_workspace_replaces_only_the_given_metadata() {
+    let workspace_name = get_workspace_name();
+    let configuration_file = configure_test(&workspace_name).unwrap_or_else(|error| {
+        panic!("Error while configuring the test: {:?}.", error);
+    });
+    let configuration =
+        get_configuration(&configuration_file).expect("Failed to read configuration.");
+    let workspace = get_workspace(&configuration, &workspace_name);
+    let address = spawn_app(&configuration, &workspace);
+    let client = reqwest::Client::new();
+    let metadata = WorkspaceMetadata {
+        description: Some("policy manual".to_string()),
+        owner: Some("legal".to_string()),
+        labels: Some(vec!["legal".to_string(), "2023".to_string()]),
+    };
+    client
+        .post(format!("{}/api/workspaces/{}", &address, &workspace_name))
+        .json(&metadata)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let metadata_update = WorkspaceMetadata {
+        description: None,
+        owner: Some("compliance".to_string()),
+        labels: Some(vec!["2024".to_string()]),
+    };
+
+    let response: Workspace = client
+        .patch(format!("{}/api/workspaces/{}", &address, &workspace_name))
+        .json(&metadata_update)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json()
+        .await
+        .expect("failed to get payload");
+
+    cleanup(&configuration_file, &configuration, &workspace_name);
+    assert_eq!(response.name, workspace_name);
+    assert_eq!(response.description, metadata.description);
+    assert_eq!(response.owner, metadata_update.owner);
+    assert_eq!(Some(response.labels), metadata_update.labels);
+    assert!(response.created_at.is_some());
+    assert!(response.updated_at >= response.created_at);
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Workspace {
+    name: String,
+    description: Option<String>,
+    owner: Option<String>,
+    labels: Vec<String>,
+    created_at: Option<i64>,
+    updated_at: Option<i64>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct WorkspaceMetadata {
+    description: Option<String>,
+    owner: Option<String>,
+    labels: Option<Vec<String>>,
+}
+
+fn get_workspace_name() -> String {
+    Uuid::new_v4().to_simple_string()
+}
+
+fn configure_test(workspace_name: &str) -> std::io::Result<PathBuf> {
+    let mut configuration_file = PathBuf::from("tests_execution");
+    configuration_file.push(workspace_name);
+    configuration_file.set_extension("yaml");
+    std::fs::copy("configuration_test.yaml", &configuration_file)?;
+    Ok(configuration_file)
+}
+
+fn cleanup(configuration_file: &Path, configuration: &Settings, workspace_name: &str) {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace.as_path());
+    }
+    if configuration_file.exists() {
+        _ = fs::remove_file(configuration_file);
+    }
+}
+
+fn spawn_app(configuration: &Settings, workspace: &Path) -> String {
+    if workspace.exists() {
+        _ = fs::remove_dir_all(workspace);
+    }
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let server = run(listener, configuration).expect("Failed to bind address");
+    tokio::spawn(server);
+    format!("http://127.0.0.1:{}", port)
+}
+
+fn get_workspace(configuration: &Settings, workspace_name: &str) -> PathBuf {
+    let mut workspace = PathBuf::from(&configuration.workspaces_path);
+    workspace.push(workspace_name);
+    workspace
+}
+
+fn create_git_repository(workspace: &Path) -> Result<Repository, git2::Error> {
+    Repository::init(workspace)
+}
+
+fn create_master_branch(repository: &Repository) -> Result<(), git2::Error> {
+    create_first_commit(repository)?;
+    let (object, reference) = repository.revparse_ext("master")?;
+    match reference {
+        Some(gref) => repository.set_head(gref.name().unwrap())?,
+        None => repository.set_head_detached(object.id())?,
+    };
+    Ok(())
+}
+
+fn create_first_commit(repository: &Repository) -> Result<git2::Oid, git2::Error> {
+    let mut index = repository.index()?;
+    let oid = index.write_tree()?;
+    let signature = Signature::now("Marco Cella", "marco.cella.tv@gmail.com")?;
+
+    let tree = repository.find_tree(oid)?;
+
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "commit message",
+        &tree,
+        &[],
+    )
+}
@@ -123,7 +123,7 @@ function Document () {
         const loadedWorkspaces = []
         for (const key in responseData) {
             loadedWorkspaces.push({
-                name: responseData[key]
+                name: responseData[key].name
             })
         }
         setWorkspaces(loadedWorkspaces)